@@ -404,6 +404,15 @@ export async function constraintsToPreConstraints(
   const forestSeeds = voronoiSeeds.forest;
   const waterSeeds = voronoiSeeds.water;
   const grassSeeds = voronoiSeeds.grass;
+  // Constraints have no mountain or sand seed counts yet
+  const mountainSeeds = 0;
+  const sandSeeds = 0;
+  // Cube distance, the original hexagonal regions
+  const metric = 0;
+  // Hexagonal chunks
+  const shape = 0;
+  // New seed per call so every recompute places the regions differently
+  const seed = Math.floor(Math.random() * 0x100000000);
 
   if (logFn) {
     logFn(`Generating Voronoi regions: ${forestSeeds} forest, ${waterSeeds} water, ${grassSeeds} grass seeds`, 'info');
@@ -418,7 +427,12 @@ export async function constraintsToPreConstraints(
       centerR,
       forestSeeds,
       waterSeeds,
-      grassSeeds
+      grassSeeds,
+      mountainSeeds,
+      sandSeeds,
+      metric,
+      shape,
+      seed
     );
     
     voronoiJson = typeof result === 'string' ? result : '[]';
@@ -320,10 +320,15 @@ export class WasmManager {
         center_r: number,
         forest_seeds: number,
         water_seeds: number,
-        grass_seeds: number
+        grass_seeds: number,
+        mountain_seeds: number,
+        sand_seeds: number,
+        metric: number,
+        shape: number,
+        seed: number
       ): string => {
         // eslint-disable-next-line @typescript-eslint/no-unsafe-call, @typescript-eslint/no-unsafe-assignment
-        const result = generateVoronoiRegionsFunc(max_layer, center_q, center_r, forest_seeds, water_seeds, grass_seeds);
+        const result = generateVoronoiRegionsFunc(max_layer, center_q, center_r, forest_seeds, water_seeds, grass_seeds, mountain_seeds, sand_seeds, metric, shape, seed);
         return typeof result === 'string' ? result : '[]';
       },
       validate_road_connectivity: (roads_json: string): boolean => {
@@ -313,7 +313,20 @@ export interface WasmModuleBabylonWfc {
 /**
  * WASM module interface for babylon-chunks (extends WasmModuleBabylonWfc with version info)
  */
-export interface WasmModuleBabylonChunks extends WasmModuleBabylonWfc {
+export interface WasmModuleBabylonChunks extends Omit<WasmModuleBabylonWfc, 'generate_voronoi_regions'> {
+  generate_voronoi_regions(
+    max_layer: number,
+    center_q: number,
+    center_r: number,
+    forest_seeds: number,
+    water_seeds: number,
+    grass_seeds: number,
+    mountain_seeds: number,
+    sand_seeds: number,
+    metric: number,
+    shape: number,
+    seed: number
+  ): string;
   get_wasm_version(): string;
   calculate_chunk_radius(rings: number): number;
   calculate_chunk_neighbors(center_q: number, center_r: number, rings: number): string;
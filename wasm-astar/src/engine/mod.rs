@@ -14,6 +14,9 @@ pub struct EngineState {
     pub fps: f64,
     pub mouse_x: i32,
     pub mouse_y: i32,
+    // Some(true) while painting walls (left button held),
+    // Some(false) while erasing them (right button held)
+    pub painting: Option<bool>,
     key_state: HashMap<u32, bool>,
     was_down: HashMap<u32, bool>,
 }
@@ -26,6 +29,7 @@ impl EngineState {
             fps: 0_f64,
             mouse_x: 0,
             mouse_y: 0,
+            painting: None,
             key_state: HashMap::new(),
             was_down: HashMap::new(),
         }
@@ -86,6 +90,20 @@ impl EngineState {
         self.mouse_x = x;
         self.mouse_y = y;
     }
+
+    // button follows MouseEvent.button: 0 = left, 2 = right
+    pub fn mouse_down(&mut self, x: i32, y: i32, button: i32) {
+        self.mouse_move(x, y);
+        self.painting = match button {
+            0 => Some(true),
+            2 => Some(false),
+            _ => None,
+        };
+    }
+
+    pub fn mouse_up(&mut self) {
+        self.painting = None;
+    }
 }
 
 #[derive(Clone)]
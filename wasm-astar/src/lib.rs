@@ -1,12 +1,12 @@
 use wasm_bindgen::prelude::*;
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, MutexGuard};
 
 mod browser;
 mod engine;
 mod utils;
 mod world;
 use engine::EngineState;
-use world::{Tile, WorldState};
+use world::{MapMode, PathfindMode, Tile, WorldState};
 
 // Imported js functions. Note, some are used in other modules (browser, utils).
 #[wasm_bindgen]
@@ -51,6 +51,12 @@ extern "C" {
 static WORLD_STATE: LazyLock<Mutex<WorldState>> = LazyLock::new(|| Mutex::new(WorldState::new()));
 static ENGINE_STATE: LazyLock<Mutex<EngineState>> = LazyLock::new(|| Mutex::new(EngineState::new()));
 
+// Recover a poisoned lock instead of panicking on every later call
+// (the state is used as the panicking call left it)
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 // Maps to WASM_ASTAR.layers on the client side
 enum Layer {
     TileBg = 0,
@@ -74,7 +80,7 @@ pub fn wasm_init(debug: i32, render_interval_ms: i32, window_width: u32, window_
     browser::create_layer("Main", Layer::Main as i32);
     browser::create_layer("Fps", Layer::Fps as i32);
     {
-        let world = &mut WORLD_STATE.lock().unwrap();
+        let world = &mut lock_or_recover(&WORLD_STATE);
         world.window_width = window_width;
         world.window_height = window_height;
         world.debug = if debug == 1 { true } else { false };
@@ -98,37 +104,148 @@ pub fn tick(elapsed_time: f64) {
 
 #[wasm_bindgen]
 pub fn key_down(key_code: u32) {
-    let engine = &mut ENGINE_STATE.lock().unwrap();
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
     engine.set_key_down(key_code);
 }
 
 #[wasm_bindgen]
 pub fn key_up(key_code: u32) {
-    let engine = &mut ENGINE_STATE.lock().unwrap();
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
     engine.set_key_up(key_code);
 }
 
 #[wasm_bindgen]
 pub fn mouse_move(x: i32, y: i32) {
-    let engine = &mut ENGINE_STATE.lock().unwrap();
-    let world = &mut WORLD_STATE.lock().unwrap();
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
+    let world = &mut lock_or_recover(&WORLD_STATE);
     engine.mouse_move(x, y);
-    world.set_player_pos(x as f64, y as f64);
+    // While a button is held the mouse paints instead of moving the start tile
+    match engine.painting {
+        Some(is_wall) => paint_wall(world, x, y, is_wall),
+        None => world.set_player_pos(x as f64, y as f64),
+    }
+}
+
+// Left button (0) paints walls, right button (2) clears them, until mouse_up
+#[wasm_bindgen]
+pub fn mouse_down(x: i32, y: i32, button: i32) {
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
+    let world = &mut lock_or_recover(&WORLD_STATE);
+    engine.mouse_down(x, y, button);
+    if let Some(is_wall) = engine.painting {
+        paint_wall(world, x, y, is_wall);
+    }
+}
+
+#[wasm_bindgen]
+pub fn mouse_up() {
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
+    engine.mouse_up();
+}
+
+fn paint_wall(world: &mut WorldState, x: i32, y: i32, is_wall: bool) {
+    if let Some(id) = world.paint_wall(x, y, is_wall) {
+        draw_tile(Layer::TileBg, &world.tiles[id]);
+    }
+}
+
+// 0 = A*, 1 = Dijkstra, 2 = Greedy best-first. Unknown modes are ignored.
+#[wasm_bindgen]
+pub fn set_pathfind_mode(mode: i32) {
+    if let Some(mode) = PathfindMode::from_i32(mode) {
+        let world = &mut lock_or_recover(&WORLD_STATE);
+        world.set_pathfind_mode(mode);
+    }
+}
+
+// 0 = randomly scattered walls, 1 = maze. Regenerates the map right away
+// and on every later spacebar press. Unknown modes are ignored.
+#[wasm_bindgen]
+pub fn set_map_mode(mode: i32) {
+    if let Some(mode) = MapMode::from_i32(mode) {
+        let world = &mut lock_or_recover(&WORLD_STATE);
+        world.map_mode = mode;
+        world.reset();
+        browser::clear_screen(Layer::Main as i32);
+        draw_background(world);
+    }
+}
+
+// x/y are client pixel coordinates, as in mouse_move. Stepping onto the tile
+// costs cost times a normal step; costs are clamped to [1, 1000].
+#[wasm_bindgen]
+pub fn set_tile_cost(x: i32, y: i32, cost: f64) {
+    let world = &mut lock_or_recover(&WORLD_STATE);
+    if let Some(id) = world.set_tile_cost(x, y, cost) {
+        draw_tile(Layer::TileBg, &world.tiles[id]);
+    }
+}
+
+// x/y are client pixel coordinates, as in mouse_move
+#[wasm_bindgen]
+pub fn set_start_tile(x: i32, y: i32) {
+    let world = &mut lock_or_recover(&WORLD_STATE);
+    world.set_start_tile(x, y);
+}
+
+#[wasm_bindgen]
+pub fn set_end_tile(x: i32, y: i32) {
+    let world = &mut lock_or_recover(&WORLD_STATE);
+    world.set_end_tile(x, y);
+}
+
+#[wasm_bindgen]
+pub fn export_map_json() -> String {
+    let world = lock_or_recover(&WORLD_STATE);
+    world.export_map_json()
+}
+
+// Malformed maps, or maps that don't fit the current canvas, are ignored.
+// Returns whether the map was loaded.
+#[wasm_bindgen]
+pub fn import_map_json(json: String) -> bool {
+    let world = &mut lock_or_recover(&WORLD_STATE);
+    if !world.import_map_json(&json) {
+        return false;
+    }
+    browser::clear_screen(Layer::Main as i32);
+    draw_background(world);
+    true
+}
+
+// Returns the current path in start-to-goal order as tile grid coordinates:
+// [{"x":0,"y":0},...], or "null" when no path exists
+#[wasm_bindgen]
+pub fn get_path_json() -> String {
+    let world = lock_or_recover(&WORLD_STATE);
+    if !world.has_path() {
+        return String::from("null");
+    }
+    let parts: Vec<String> = world
+        .path_ids_from_end()
+        .iter()
+        .rev()
+        .map(|id| {
+            let t = &world.tiles[*id];
+            format!(r#"{{"x":{},"y":{}}}"#, t.x_id, t.y_id)
+        })
+        .collect();
+    format!("[{}]", parts.join(","))
 }
 
 fn update(elapsed_time: f64) {
     handle_input();
-    let engine = &mut ENGINE_STATE.lock().unwrap();
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
     engine.update(elapsed_time);
-    let world = &mut WORLD_STATE.lock().unwrap();
+    let world = &mut lock_or_recover(&WORLD_STATE);
     world.set_start_node();
     world.calc_astar();
     js_update();
 }
 
 fn handle_input() {
-    let world = &mut WORLD_STATE.lock().unwrap();
-    let engine = &mut ENGINE_STATE.lock().unwrap();
+    let world = &mut lock_or_recover(&WORLD_STATE);
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
     if !engine.was_key_down(engine::KeyCode::Spacebar)
         && engine.is_key_down(engine::KeyCode::Spacebar)
         && !world.recent_regen
@@ -157,7 +274,7 @@ fn handle_input() {
 }
 
 fn initial_draw() {
-    let world = &mut WORLD_STATE.lock().unwrap();
+    let world = &mut lock_or_recover(&WORLD_STATE);
     if world.window_width < 600 {
         world.width = 350 * world.quality;
         world.height = 450 * world.quality;
@@ -176,11 +293,11 @@ fn initial_draw() {
 }
 
 fn draw(elapsed_time: f64) {
-    let world = &mut WORLD_STATE.lock().unwrap();
+    let world = &mut lock_or_recover(&WORLD_STATE);
     if world.recent_regen {
         draw_background(world);
     }
-    draw_path(world, &world.tiles[world.end_id as usize]);
+    draw_path(world);
     draw_tile_with_color(
         Layer::Main,
         &world.tiles[world.start_id as usize],
@@ -191,7 +308,7 @@ fn draw(elapsed_time: f64) {
         &world.tiles[world.end_id as usize],
         &engine::Color::new(112, 89, 61, 1.0),
     );
-    let path_count = get_path_count(world, &world.tiles[world.end_id as usize], 0);
+    let path_count = get_path_count(world);
     draw_path_count(path_count);
     // draw_player(world);
     draw_fps(elapsed_time);
@@ -203,29 +320,25 @@ fn draw_background(world: &WorldState) {
     }
 }
 
-fn draw_path(world: &WorldState, t: &Tile) {
+fn draw_path(world: &WorldState) {
     let half_tile = (world.tile_size / 2) as f64;
-    js_draw_circle(
-        Layer::Main as i32,
-        t.transform.pos_x + half_tile,
-        t.transform.pos_y + half_tile,
-        t.transform.scale_x / 5_f64,
-        280,
-        100,
-        73,
-        1_f32,
-    );
-    if t.parent_id >= 0 {
-        draw_path(world, &world.tiles[t.parent_id as usize]);
+    for id in world.path_ids_from_end() {
+        let t = &world.tiles[id];
+        js_draw_circle(
+            Layer::Main as i32,
+            t.transform.pos_x + half_tile,
+            t.transform.pos_y + half_tile,
+            t.transform.scale_x / 5_f64,
+            280,
+            100,
+            73,
+            1_f32,
+        );
     }
 }
 
-fn get_path_count(world: &WorldState, t: &Tile, counter: i32) -> i32 {
-    if t.parent_id >= 0 {
-        get_path_count(world, &world.tiles[t.parent_id as usize], counter + 1)
-    } else {
-        counter
-    }
+fn get_path_count(world: &WorldState) -> i32 {
+    world.path_ids_from_end().len() as i32 - 1
 }
 
 fn draw_tile(layer: Layer, t: &Tile) {
@@ -250,7 +363,7 @@ fn draw_path_count(path_count: i32) {
 }
 
 fn draw_fps(elapsed_time: f64) {
-    let engine = &mut ENGINE_STATE.lock().unwrap();
+    let engine = &mut lock_or_recover(&ENGINE_STATE);
     let fps = engine.fps;
     engine.render_fps(elapsed_time, 150, || {
         browser::clear_screen(Layer::Fps as i32);
@@ -1,10 +1,66 @@
 use std::collections::HashSet;
 
-use crate::engine::{Color, Transform};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Transform;
 use crate::utils::{log_fmt, random, random_range};
 
 mod tile;
-pub use self::tile::Tile;
+pub use self::tile::{Tile, MAX_COST};
+
+// Selects how calc_astar scores nodes:
+// A* uses f = g + h, Dijkstra ignores the heuristic (h = 0),
+// greedy best-first ignores the distance travelled (g = 0)
+#[derive(Clone, Copy, PartialEq)]
+pub enum PathfindMode {
+    AStar = 0,
+    Dijkstra = 1,
+    Greedy = 2,
+}
+
+impl PathfindMode {
+    pub fn from_i32(mode: i32) -> Option<PathfindMode> {
+        match mode {
+            0 => Some(PathfindMode::AStar),
+            1 => Some(PathfindMode::Dijkstra),
+            2 => Some(PathfindMode::Greedy),
+            _ => None,
+        }
+    }
+}
+
+// How reset() builds a new map: randomly scattered walls, or a maze
+#[derive(Clone, Copy, PartialEq)]
+pub enum MapMode {
+    Scatter = 0,
+    Maze = 1,
+}
+
+impl MapMode {
+    pub fn from_i32(mode: i32) -> Option<MapMode> {
+        match mode {
+            0 => Some(MapMode::Scatter),
+            1 => Some(MapMode::Maze),
+            _ => None,
+        }
+    }
+}
+
+// Saved map format for export_map_json / import_map_json.
+// walls is row-major with one entry per tile: 1 = wall, 0 = walkable.
+// costs is row-major tile weights (>= 1); it may be left out, meaning all 1.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MapData {
+    tile_size: u32,
+    columns: u32,
+    rows: u32,
+    start_id: i32,
+    end_id: i32,
+    walls: Vec<u8>,
+    #[serde(default)]
+    costs: Vec<f64>,
+}
 
 pub struct WorldState {
     pub debug: bool,
@@ -19,6 +75,11 @@ pub struct WorldState {
     pub player: Transform,
     pub tiles: Vec<Tile>,
     pub recent_regen: bool,
+    pub pathfind_mode: PathfindMode,
+    pub map_mode: MapMode,
+    // Set whenever the start/end tiles, walls or pathfind mode change.
+    // While clear, calc_astar keeps the previous result (the tiles' parent_ids).
+    path_dirty: bool,
 }
 
 impl WorldState {
@@ -41,13 +102,20 @@ impl WorldState {
             start_id: -1,
             end_id: -1,
             recent_regen: false,
+            pathfind_mode: PathfindMode::AStar,
+            map_mode: MapMode::Scatter,
+            path_dirty: true,
         };
         w.reset();
         w
     }
 
     pub fn reset(&mut self) {
-        self.load_random_map();
+        self.path_dirty = true;
+        match self.map_mode {
+            MapMode::Scatter => self.load_random_map(),
+            MapMode::Maze => self.generate_maze(),
+        }
         // self.load_test_map();
     }
 
@@ -64,12 +132,30 @@ impl WorldState {
 
     pub fn set_start_node(&mut self) {
         let half_tile = (self.tile_size / 2) as f64;
-        self.start_id = self
+        let start_id = self
             .get_tile_id_closest_to(self.player.pos_x - half_tile, self.player.pos_y - half_tile)
             as i32;
+        // Player moves within the same tile don't change the path
+        if start_id != self.start_id {
+            self.start_id = start_id;
+            self.path_dirty = true;
+        }
+    }
+
+    pub fn set_pathfind_mode(&mut self, mode: PathfindMode) {
+        if mode != self.pathfind_mode {
+            self.pathfind_mode = mode;
+            self.path_dirty = true;
+        }
     }
 
     pub fn calc_astar(&mut self) {
+        // Nothing changed since the last search, so its path is still valid
+        if !self.path_dirty {
+            return;
+        }
+        self.path_dirty = false;
+
         let mut open_nodes: Vec<usize> = Vec::new();
         let mut closed_nodes = HashSet::new();
 
@@ -79,6 +165,14 @@ impl WorldState {
 
         for t in self.tiles.iter_mut() {
             t.reset(&end);
+            if self.pathfind_mode == PathfindMode::Dijkstra {
+                t.h = 0;
+            }
+        }
+
+        // Walls are impassable, so there's no path from or to one
+        if self.tiles[self.start_id as usize].is_wall || end.is_wall {
+            return;
         }
 
         // Stop searching when either:
@@ -126,6 +220,67 @@ impl WorldState {
         }
     }
 
+    // Mark the tile under the client pixel (x, y) as a wall or clear it.
+    // Returns the tile id if it changed so the caller can redraw it.
+    // The start and end tiles can't be walled over.
+    pub fn paint_wall(&mut self, x: i32, y: i32, is_wall: bool) -> Option<usize> {
+        let id = self.get_tile_id_at_pixel(x, y)?;
+        if self.tiles[id].is_wall == is_wall {
+            return None;
+        }
+        if is_wall && (id as i32 == self.start_id || id as i32 == self.end_id) {
+            return None;
+        }
+        self.tiles[id].set_wall(is_wall);
+        self.set_all_tile_sides();
+        Some(id)
+    }
+
+    // Set the traversal weight of the tile under the client pixel (x, y).
+    // Returns the tile id if it changed so the caller can redraw it.
+    // Non-finite costs and out-of-bounds coordinates are ignored.
+    pub fn set_tile_cost(&mut self, x: i32, y: i32, cost: f64) -> Option<usize> {
+        if !cost.is_finite() {
+            return None;
+        }
+        let id = self.get_tile_id_at_pixel(x, y)?;
+        self.tiles[id].set_cost(cost);
+        self.path_dirty = true;
+        Some(id)
+    }
+
+    // Place the start tile under the client pixel (x, y). The start follows the
+    // player, so the player is moved onto that tile too. Out-of-bounds is ignored.
+    pub fn set_start_tile(&mut self, x: i32, y: i32) {
+        if let Some(id) = self.get_tile_id_at_pixel(x, y) {
+            self.player.pos_x = self.tiles[id].transform.pos_x;
+            self.player.pos_y = self.tiles[id].transform.pos_y;
+            self.start_id = id as i32;
+            self.path_dirty = true;
+        }
+    }
+
+    // Place the end tile under the client pixel (x, y). Out-of-bounds is ignored.
+    pub fn set_end_tile(&mut self, x: i32, y: i32) {
+        if let Some(id) = self.get_tile_id_at_pixel(x, y) {
+            self.end_id = id as i32;
+            self.path_dirty = true;
+        }
+    }
+
+    fn get_tile_id_at_pixel(&self, x: i32, y: i32) -> Option<usize> {
+        let px = x as i64 * self.quality as i64;
+        let py = y as i64 * self.quality as i64;
+        let num_x_tiles = (self.width / self.tile_size) as i64;
+        let num_y_tiles = (self.height / self.tile_size) as i64;
+        let x_id = px.div_euclid(self.tile_size as i64);
+        let y_id = py.div_euclid(self.tile_size as i64);
+        if x_id < 0 || y_id < 0 || x_id >= num_x_tiles || y_id >= num_y_tiles {
+            return None;
+        }
+        Some(self.get_tile_id_at(x_id as u32, y_id as u32))
+    }
+
     fn check_node(
         &mut self,
         open_nodes: &mut Vec<usize>,
@@ -142,16 +297,95 @@ impl WorldState {
             parent_g = self.tiles[parent_id].g;
         }
         // if it's already on the open list and the path is better (lower G value)
-        else if self.tiles[id].g > self.tiles[curr_node_id].g + tile::MOVE_COST {
+        else if self.tiles[id].g > self.tiles[curr_node_id].g.saturating_add(self.tiles[id].move_cost()) {
             parent_id = curr_node_id;
             parent_g = self.tiles[parent_id].g;
         }
         if parent_g != -1 {
             self.tiles[id].parent_id = parent_id as i32;
             self.tiles[id].calc_f_g(parent_g);
+            if self.pathfind_mode == PathfindMode::Greedy {
+                self.tiles[id].g = 0;
+                self.tiles[id].f = self.tiles[id].h;
+            }
         }
     }
 
+    // Tile ids from the end tile back along parent_id, ending at the first tile
+    // without a parent (the start tile when a path was found).
+    // Walks in a loop rather than recursing so long paths can't overflow the WASM stack.
+    pub fn path_ids_from_end(&self) -> Vec<usize> {
+        let mut ids = vec![self.end_id as usize];
+        let mut current = &self.tiles[self.end_id as usize];
+        while current.parent_id >= 0 {
+            ids.push(current.parent_id as usize);
+            current = &self.tiles[current.parent_id as usize];
+        }
+        ids
+    }
+
+    pub fn has_path(&self) -> bool {
+        self.start_id == self.end_id || self.tiles[self.end_id as usize].parent_id >= 0
+    }
+
+    pub fn export_map_json(&self) -> String {
+        let map = MapData {
+            tile_size: self.tile_size,
+            columns: self.width / self.tile_size,
+            rows: self.height / self.tile_size,
+            start_id: self.start_id,
+            end_id: self.end_id,
+            walls: self.tiles.iter().map(|t| t.is_wall as u8).collect(),
+            costs: self.tiles.iter().map(|t| t.cost).collect(),
+        };
+        serde_json::to_string(&map).unwrap_or_default()
+    }
+
+    // Rebuilds the tiles from an exported map. The map must fit the current canvas
+    // (columns * tile_size and rows * tile_size match it), have one 0/1 wall entry
+    // per tile, costs (if present) in [1, MAX_COST], and in-range start/end tiles that
+    // aren't walls.
+    // Anything else is ignored and leaves the current map untouched.
+    // Returns whether the map was loaded.
+    pub fn import_map_json(&mut self, json: &str) -> bool {
+        let map: MapData = match serde_json::from_str(json) {
+            Ok(map) => map,
+            Err(_) => return false,
+        };
+        if map.tile_size == 0
+            || map.columns != self.width / map.tile_size
+            || map.rows != self.height / map.tile_size
+            || map.walls.len() != (map.columns * map.rows) as usize
+            || map.walls.iter().any(|w| *w > 1)
+            || (!map.costs.is_empty() && map.costs.len() != map.walls.len())
+            || map.costs.iter().any(|c| !c.is_finite() || *c < 1_f64 || *c > MAX_COST)
+        {
+            return false;
+        }
+        let in_range = |id: i32| id >= 0 && (id as usize) < map.walls.len();
+        if !in_range(map.start_id)
+            || !in_range(map.end_id)
+            || map.walls[map.start_id as usize] == 1
+            || map.walls[map.end_id as usize] == 1
+        {
+            return false;
+        }
+
+        self.tile_size = map.tile_size;
+        self.tiles = build_tiles(map.columns, map.rows, map.tile_size, |id| map.walls[id] == 1);
+        for (t, cost) in self.tiles.iter_mut().zip(map.costs.iter()) {
+            t.set_cost(*cost);
+        }
+        self.set_all_tile_sides();
+        self.start_id = map.start_id;
+        self.end_id = map.end_id;
+        self.player.pos_x = self.tiles[self.start_id as usize].transform.pos_x;
+        self.player.pos_y = self.tiles[self.start_id as usize].transform.pos_y;
+        self.set_start_node();
+        self.calc_astar();
+        true
+    }
+
     #[allow(dead_code)]
     fn get_tile_at(&mut self, x: u32, y: u32) -> &mut Tile {
         let index = self.get_tile_id_at(x, y);
@@ -201,7 +435,13 @@ impl WorldState {
     fn set_all_tile_sides(&mut self) {
         let num_x_tiles = (self.width / self.tile_size) as i32;
         let num_y_tiles = (self.height / self.tile_size) as i32;
+        self.path_dirty = true;
         for t_id in 0..self.tiles.len() {
+            // Clear sides first so walls painted since the last call are dropped
+            self.tiles[t_id].top = -1;
+            self.tiles[t_id].bottom = -1;
+            self.tiles[t_id].left = -1;
+            self.tiles[t_id].right = -1;
             let x_id = self.tiles[t_id].x_id;
             let y_id = self.tiles[t_id].y_id;
             if x_id + 1 < num_x_tiles {
@@ -253,8 +493,7 @@ impl WorldState {
     }
 
     fn load_random_map(&mut self) {
-        let tile_sizes = vec![10, 20, 50];
-        self.tile_size = tile_sizes[random_range(0, (tile_sizes.len() - 1) as i32) as usize];
+        self.tile_size = random_tile_size();
         self.tiles = generate_tiles(self.width, self.height, self.tile_size);
         self.set_all_tile_sides();
         self.set_target_tiles();
@@ -267,6 +506,63 @@ impl WorldState {
         }
     }
 
+    // Carves a perfect maze (exactly one route between any two open tiles) with
+    // randomized depth-first backtracking. Maze cells sit on even x/y ids and the
+    // odd ids between them start as walls; knocking one out joins two cells.
+    // An explicit stack is used instead of recursion so big grids can't overflow
+    // the WASM stack. Start and end are placed at opposite corners.
+    pub fn generate_maze(&mut self) {
+        self.tile_size = random_tile_size();
+        let num_x_tiles = self.width / self.tile_size;
+        let num_y_tiles = self.height / self.tile_size;
+        self.tiles = build_tiles(num_x_tiles, num_y_tiles, self.tile_size, |_| true);
+
+        let cells_x = num_x_tiles.div_ceil(2) as i32;
+        let cells_y = num_y_tiles.div_ceil(2) as i32;
+        let mut visited = vec![false; (cells_x * cells_y) as usize];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        self.open_tile(0, 0);
+
+        while let Some(&(cx, cy)) = stack.last() {
+            let unvisited: Vec<(i32, i32)> = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .map(|(dx, dy)| (cx + dx, cy + dy))
+                .filter(|(nx, ny)| {
+                    *nx >= 0
+                        && *ny >= 0
+                        && *nx < cells_x
+                        && *ny < cells_y
+                        && !visited[(ny * cells_x + nx) as usize]
+                })
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+
+            let (nx, ny) = unvisited[random_range(0, unvisited.len() as i32 - 1) as usize];
+            visited[(ny * cells_x + nx) as usize] = true;
+            // Knock out the wall between the two cells, then open the new cell
+            self.open_tile((cx + nx) as u32, (cy + ny) as u32);
+            self.open_tile((nx * 2) as u32, (ny * 2) as u32);
+            stack.push((nx, ny));
+        }
+
+        self.set_all_tile_sides();
+        self.start_id = self.get_tile_id_at(0, 0) as i32;
+        self.end_id = self.get_tile_id_at(((cells_x - 1) * 2) as u32, ((cells_y - 1) * 2) as u32) as i32;
+        self.player.pos_x = self.tiles[self.start_id as usize].transform.pos_x;
+        self.player.pos_y = self.tiles[self.start_id as usize].transform.pos_y;
+        self.set_start_node();
+        self.calc_astar();
+    }
+
+    fn open_tile(&mut self, x: u32, y: u32) {
+        let id = self.get_tile_id_at(x, y);
+        self.tiles[id].set_wall(false);
+    }
+
     #[allow(dead_code)]
     fn load_test_map(&mut self) {
         let test_map = "0,0,1,1,1,0,0,0,0,0,0,0,0,0,1,1,1,1,1,0,1,0,1,0,0,1,0,0,0,1,1,0,1,0,0,0,
@@ -304,6 +600,11 @@ impl WorldState {
     }
 }
 
+fn random_tile_size() -> u32 {
+    let tile_sizes = [10, 20, 50];
+    tile_sizes[random_range(0, (tile_sizes.len() - 1) as i32) as usize]
+}
+
 #[allow(dead_code)]
 fn load_map(tile_size: u32, map: &str) -> Vec<Tile> {
     let mut vec = Vec::new();
@@ -320,15 +621,7 @@ fn load_map(tile_size: u32, map: &str) -> Vec<Tile> {
             t.x_id = x as i32;
             t.y_id = y as i32;
             t.node_id = (y * num_cols + x) as usize;
-            t.is_wall = {
-                if String::from(*col).eq("1") {
-                    true
-                } else {
-                    false
-                }
-            };
-            let lightness = if t.is_wall { 20 } else { 30 };
-            t.color = Color::new(0, 0, lightness, 1_f32);
+            t.set_wall(String::from(*col).eq("1"));
             vec.push(t);
         }
     }
@@ -336,9 +629,20 @@ fn load_map(tile_size: u32, map: &str) -> Vec<Tile> {
 }
 
 fn generate_tiles(grid_width: u32, grid_height: u32, tile_size: u32) -> Vec<Tile> {
+    build_tiles(
+        grid_width / tile_size,
+        grid_height / tile_size,
+        tile_size,
+        |_| random() >= 0.7,
+    )
+}
+
+// Lays out a num_x_tiles x num_y_tiles grid; is_wall gets each tile's node id
+fn build_tiles<F>(num_x_tiles: u32, num_y_tiles: u32, tile_size: u32, is_wall: F) -> Vec<Tile>
+where
+    F: Fn(usize) -> bool,
+{
     let mut vec = Vec::new();
-    let num_y_tiles = grid_height / tile_size;
-    let num_x_tiles = grid_width / tile_size;
 
     for y in 0..num_y_tiles {
         for x in 0..num_x_tiles {
@@ -349,15 +653,7 @@ fn generate_tiles(grid_width: u32, grid_height: u32, tile_size: u32) -> Vec<Tile
             t.x_id = x as i32;
             t.y_id = y as i32;
             t.node_id = (y * num_x_tiles + x) as usize;
-            t.is_wall = {
-                if random() >= 0.7 {
-                    true
-                } else {
-                    false
-                }
-            };
-            let lightness = if t.is_wall { 20 } else { 30 };
-            t.color = Color::new(0, 0, lightness, 1_f32);
+            t.set_wall(is_wall(t.node_id));
             vec.push(t);
         }
     }
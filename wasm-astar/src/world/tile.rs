@@ -1,6 +1,9 @@
 use crate::engine::{Color, Transform};
 
 pub const MOVE_COST: i32 = 10;
+// Highest traversal weight a tile can have. Keeps a single step at 10,000, so g sums
+// over a whole path stay far from i32::MAX.
+pub const MAX_COST: f64 = 1000_f64;
 
 #[derive(Clone)]
 pub struct Tile {
@@ -15,6 +18,8 @@ pub struct Tile {
     pub left: i32,
     pub right: i32,
     pub is_wall: bool,
+    // Traversal weight: stepping onto this tile costs MOVE_COST * cost
+    pub cost: f64,
     // A* values
     // TODO: move to a new struct type just for A*
     pub h: i32,
@@ -36,12 +41,43 @@ impl Tile {
             left: -1,
             right: -1,
             is_wall: false,
+            cost: 1_f64,
             h: 0,
             g: 0,
             f: 0,
         }
     }
 
+    pub fn set_wall(&mut self, is_wall: bool) {
+        self.is_wall = is_wall;
+        self.update_color();
+    }
+
+    // Costs below 1 would make the Manhattan heuristic overestimate, so they're raised to 1.
+    // Costs above MAX_COST are lowered to it.
+    pub fn set_cost(&mut self, cost: f64) {
+        self.cost = cost.clamp(1_f64, MAX_COST);
+        self.update_color();
+    }
+
+    // Cost of stepping onto this tile
+    pub fn move_cost(&self) -> i32 {
+        (MOVE_COST as f64 * self.cost).round() as i32
+    }
+
+    // Walls are dark gray, plain floor light gray, and weighted floor brown,
+    // getting darker as the cost rises
+    fn update_color(&mut self) {
+        self.color = if self.is_wall {
+            Color::new(0, 0, 20, 1_f32)
+        } else if self.cost > 1_f64 {
+            let lightness = (32_f64 - self.cost * 2_f64).max(10_f64) as u16;
+            Color::new(30, 35, lightness, 1_f32)
+        } else {
+            Color::new(0, 0, 30, 1_f32)
+        };
+    }
+
     pub fn reset(&mut self, end_node: &Tile) {
         self.parent_id = -1;
         self.g = 0;
@@ -64,7 +100,23 @@ impl Tile {
     }
 
     pub fn calc_f_g(&mut self, parent_g: i32) {
-        self.g = parent_g + MOVE_COST;
-        self.f = self.g + self.h;
+        self.g = parent_g.saturating_add(self.move_cost());
+        self.f = self.g.saturating_add(self.h);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_costs_are_capped() {
+        let mut tile = Tile::new(0_f64, 0_f64, 10_f64);
+        tile.set_cost(1e12);
+        assert_eq!(tile.cost, MAX_COST);
+        assert_eq!(tile.move_cost(), 10_000);
+        tile.calc_f_g(i32::MAX - 5);
+        assert_eq!(tile.g, i32::MAX);
+        assert_eq!(tile.f, i32::MAX);
     }
 }
@@ -1,9 +1,10 @@
 /// A* pathfinding module
 
 use wasm_bindgen::prelude::*;
-use std::collections::{HashMap, HashSet, BinaryHeap};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, BinaryHeap, VecDeque};
 use crate::types::AStarNode;
-use crate::hex_utils::{get_hex_neighbors, parse_valid_terrain_json, axial_to_cube, cube_distance, hex_distance};
+use crate::hex_utils::{get_hex_neighbors, connected_components, parse_coords, parse_valid_terrain_json, parse_path_json, parse_cost_json, axial_to_cube, cube_distance, hex_distance, CUBE_DIRECTIONS};
 
 /// Hex A* pathfinding between two road tiles
 /// Returns path length, or -1 if unreachable
@@ -89,10 +90,55 @@ pub fn hex_astar_path(
     -1
 }
 
+/// Maximum number of points accepted by `hex_distance_matrix`
+const MAX_MATRIX_POINTS: usize = 64;
+
+/// Shortest step counts from a start tile to every reachable tile
+/// With uniform step cost, breadth-first search visits tiles in Dijkstra order
+pub fn hex_bfs_distances(start_q: i32, start_r: i32, walkable: &HashSet<(i32, i32)>) -> HashMap<(i32, i32), i32> {
+    let mut distances: HashMap<(i32, i32), i32> = HashMap::new();
+    if !walkable.contains(&(start_q, start_r)) {
+        return distances;
+    }
+    
+    let mut queue = VecDeque::new();
+    distances.insert((start_q, start_r), 0);
+    queue.push_back((start_q, start_r));
+    
+    while let Some((q, r)) = queue.pop_front() {
+        let distance = distances[&(q, r)];
+        for neighbor in get_hex_neighbors(q, r) {
+            if walkable.contains(&neighbor) && !distances.contains_key(&neighbor) {
+                distances.insert(neighbor, distance + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    
+    distances
+}
+
+/// Weighted hex A* over per-tile movement costs
+/// Returns the path (start to goal, inclusive) and its total cost, or None if unreachable
+/// 
+/// - Stepping onto a tile costs that tile's entry in `costs` (the start tile is free)
+/// - Tiles absent from `costs` are impassable
+/// - h_cost is hex distance scaled by the cheapest tile cost, which keeps it admissible
+pub fn weighted_astar_path(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    costs: &HashMap<(i32, i32), i32>,
+) -> Option<CostedPath> {
+    let min_cost = costs.values().copied().min().unwrap_or(1);
+    hex_astar_search(start_q, start_r, goal_q, goal_r, |key| costs.get(&key).copied(), min_cost).0
+}
+
 /// Hex A* pathfinding that returns full path
 /// Matches TypeScript hexAStar algorithm exactly:
 /// - Uses cube coordinates for distance calculation (cube_distance)
-/// - Maintains open set as BinaryHeap (min-heap by f score, then h score)
+/// - Maintains open set as an indexed min-heap (by f score, then h score) with one entry per tile
 /// - Maintains closed set as HashSet
 /// - Maintains g_scores as HashMap
 /// - Stores parent pointers for path reconstruction
@@ -114,14 +160,274 @@ pub fn hex_astar(
     // Parse valid terrain from JSON
     let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
     
-    // Check if start and goal are in valid terrain
-    if !valid_terrain.contains(&(start_q, start_r)) || !valid_terrain.contains(&(goal_q, goal_r)) {
-        return "null".to_string();
+    let path = match hex_astar_checked(start_q, start_r, goal_q, goal_r, &valid_terrain) {
+        Ok(path) => path,
+        Err(_) => return "null".to_string(),
+    };
+    
+    // Build JSON string
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Hex A* pathfinding over packed coordinate arrays
+/// 
+/// **Learning Point**: Same search as `hex_astar` without JSON on either side. The
+/// terrain arrives as a flat `[q, r, q, r, ...]` `Int32Array` and the path leaves as
+/// one, so hot pathfinding loops skip `JSON.stringify` on the way in and `JSON.parse`
+/// on the way out. A trailing unpaired value in `terrain` is ignored.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param terrain - Int32Array of valid terrain coordinates: [q, r, q, r, ...]
+/// @returns Int32Array path [q, r, q, r, ...] from start to goal inclusive, or empty if no path found
+#[wasm_bindgen]
+pub fn hex_astar_packed(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    terrain: &[i32],
+) -> Vec<i32> {
+    let valid_terrain: HashSet<(i32, i32)> = terrain.chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    
+    let path = match hex_astar_route(start_q, start_r, goal_q, goal_r, &valid_terrain) {
+        Some(path) => path,
+        None => return Vec::new(),
+    };
+    
+    let mut packed = Vec::with_capacity(path.len() * 2);
+    for (q, r) in path {
+        packed.extend_from_slice(&[q, r]);
+    }
+    packed
+}
+
+/// Hex A* pathfinding with a structured result
+/// 
+/// **Learning Point**: `hex_astar` collapses every failure into "null", so JS can't tell
+/// a typo'd start tile from a goal that is walled off. This variant always returns an
+/// object whose `status` names the outcome:
+/// - "ok": `path` holds the route (start to goal, inclusive)
+/// - "start_invalid": the start tile is not in the valid terrain
+/// - "goal_invalid": the goal tile is not in the valid terrain (checked after start)
+/// - "unreachable": both tiles are valid but no path connects them
+/// `path` is an empty array for every status except "ok".
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
+/// @returns JSON object {"status":"ok","path":[{"q":0,"r":0},...]}
+#[wasm_bindgen]
+pub fn hex_astar_result(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain_json: String,
+) -> String {
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let (status, path) = match hex_astar_checked(start_q, start_r, goal_q, goal_r, &valid_terrain) {
+        Ok(path) => ("ok", path),
+        Err(status) => (status, Vec::new()),
+    };
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!(r#"{{"status":"{}","path":[{}]}}"#, status, json_parts.join(","))
+}
+
+/// Hex A* that names why no path was found
+/// Returns the path, or Err with "start_invalid", "goal_invalid" or "unreachable"
+fn hex_astar_checked(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain: &HashSet<(i32, i32)>,
+) -> Result<Vec<(i32, i32)>, &'static str> {
+    if !valid_terrain.contains(&(start_q, start_r)) {
+        return Err("start_invalid");
+    }
+    if !valid_terrain.contains(&(goal_q, goal_r)) {
+        return Err("goal_invalid");
+    }
+    
+    hex_astar_route(start_q, start_r, goal_q, goal_r, valid_terrain).ok_or("unreachable")
+}
+
+/// Hex A* over a walkable set, returning the path (start to goal, inclusive)
+/// Returns None if start or goal is not walkable or no path exists
+pub fn hex_astar_route(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain: &HashSet<(i32, i32)>,
+) -> Option<Vec<(i32, i32)>> {
+    uniform_astar_search(start_q, start_r, goal_q, goal_r, valid_terrain).0
+}
+
+/// Binary min-heap of A* nodes with at most one entry per coordinate
+/// `positions` maps each queued coordinate to its slot in `nodes`, so a better path to a
+/// queued node updates that entry in place (decrease-key) instead of pushing a duplicate
+struct IndexedHeap {
+    nodes: Vec<AStarNode>,
+    positions: HashMap<(i32, i32), usize>,
+}
+
+impl IndexedHeap {
+    fn new() -> Self {
+        IndexedHeap {
+            nodes: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+    
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    
+    /// Queue a node, or replace the queued entry for the same coordinate
+    /// Callers only replace an entry with a better one, so it can only move up
+    /// Returns true if an existing entry was replaced
+    fn push_or_decrease(&mut self, node: AStarNode) -> bool {
+        match self.positions.get(&(node.q, node.r)) {
+            Some(&index) => {
+                self.nodes[index] = node;
+                self.sift_up(index);
+                true
+            }
+            None => {
+                self.nodes.push(node);
+                self.positions.insert((node.q, node.r), self.nodes.len() - 1);
+                self.sift_up(self.nodes.len() - 1);
+                false
+            }
+        }
+    }
+    
+    /// Remove and return the node with the lowest f (then h)
+    fn pop(&mut self) -> Option<AStarNode> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let last = self.nodes.len() - 1;
+        self.swap(0, last);
+        let node = self.nodes.pop()?;
+        self.positions.remove(&(node.q, node.r));
+        if !self.nodes.is_empty() {
+            self.sift_down(0);
+        }
+        Some(node)
+    }
+    
+    // AStarNode orders the best node as greatest, as BinaryHeap expects
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.nodes[index] <= self.nodes[parent] {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+    
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let left = 2 * index + 1;
+            let right = left + 1;
+            let mut best = index;
+            if left < self.nodes.len() && self.nodes[left] > self.nodes[best] {
+                best = left;
+            }
+            if right < self.nodes.len() && self.nodes[right] > self.nodes[best] {
+                best = right;
+            }
+            if best == index {
+                break;
+            }
+            self.swap(index, best);
+            index = best;
+        }
+    }
+    
+    fn swap(&mut self, a: usize, b: usize) {
+        self.nodes.swap(a, b);
+        self.positions.insert((self.nodes[a].q, self.nodes[a].r), a);
+        self.positions.insert((self.nodes[b].q, self.nodes[b].r), b);
+    }
+}
+
+/// Search effort counters collected by `hex_astar_search`
+#[derive(Clone, Copy, Debug, Default)]
+struct SearchStats {
+    /// Nodes moved to the closed set
+    expanded: usize,
+    /// Peak number of entries in the open set
+    max_open: usize,
+    /// Times a node already in the open set had its g improved
+    reopened: usize,
+}
+
+/// Hex A* with a uniform step cost of 1 over a walkable set
+fn uniform_astar_search(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain: &HashSet<(i32, i32)>,
+) -> (Option<Vec<(i32, i32)>>, SearchStats) {
+    let step_cost = |key| if valid_terrain.contains(&key) { Some(1) } else { None };
+    let (result, stats) = hex_astar_search(start_q, start_r, goal_q, goal_r, step_cost, 1);
+    (result.map(|(path, _)| path), stats)
+}
+
+/// A path (start to goal, inclusive) and its total step cost
+type CostedPath = (Vec<(i32, i32)>, i32);
+
+/// Hex A* search shared by `uniform_astar_search` and `weighted_astar_path`
+/// 
+/// - `step_cost(tile)` is the cost of stepping onto `tile`, or None if it is impassable;
+///   the start tile is free but must itself be passable
+/// - h_cost is cube distance times `min_step_cost`, which must not exceed any step cost
+///   so the heuristic stays admissible
+/// 
+/// Returns the path (start to goal, inclusive) with its total cost, or None if start or
+/// goal is impassable or no path exists, together with how much work the search did
+fn hex_astar_search(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    step_cost: impl Fn((i32, i32)) -> Option<i32>,
+    min_step_cost: i32,
+) -> (Option<CostedPath>, SearchStats) {
+    let mut stats = SearchStats::default();
+    
+    // Check if start and goal are passable
+    if step_cost((start_q, start_r)).is_none() || step_cost((goal_q, goal_r)).is_none() {
+        return (None, stats);
     }
     
     // If start equals goal, return path with single node
     if start_q == goal_q && start_r == goal_r {
-        return format!(r#"[{{"q":{},"r":{}}}]"#, start_q, start_r);
+        return (Some((vec![(start_q, start_r)], 0)), stats);
     }
     
     // Convert goal to cube for distance calculation (matches TypeScript)
@@ -130,192 +436,533 @@ pub fn hex_astar(
     // Calculate heuristic function (cube distance)
     let heuristic = |q: i32, r: i32| -> i32 {
         let cube = axial_to_cube(q, r);
-        cube_distance(cube, goal_cube)
+        cube_distance(cube, goal_cube).saturating_mul(min_step_cost)
     };
     
     // Initialize A* data structures
     let h_start = heuristic(start_q, start_r);
-    let mut open_set = BinaryHeap::new();
+    let mut open_set = IndexedHeap::new();
     let mut closed_set = HashSet::new();
     let mut g_scores: HashMap<(i32, i32), i32> = HashMap::new();
     let mut parents: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
     
     // Start node (parent is itself to mark as root)
-    open_set.push(AStarNode::new(start_q, start_r, 0, h_start, start_q, start_r));
+    open_set.push_or_decrease(AStarNode::new(start_q, start_r, 0, h_start, start_q, start_r));
     g_scores.insert((start_q, start_r), 0);
+    stats.max_open = 1;
     
+    // Each coordinate has at most one open entry, so every pop is a fresh node
     while let Some(current) = open_set.pop() {
         let current_key = (current.q, current.r);
-        
-        // Skip if already processed (duplicate in open_set)
-        if closed_set.contains(&current_key) {
-            continue;
-        }
-        
         closed_set.insert(current_key);
+        stats.expanded += 1;
         
         // Check if we reached the goal
-        if current.q == goal_q && current.r == goal_r {
-            // Reconstruct path by following parent pointers
-            let mut path: Vec<(i32, i32)> = Vec::new();
-            let mut node_key = (goal_q, goal_r);
-            
-            // Follow parent pointers from goal to start
-            loop {
-                path.push(node_key);
-                
-                // Get parent for this node
-                if let Some(parent_key) = parents.get(&node_key) {
-                    // If parent is the start, add it and break
-                    if parent_key.0 == start_q && parent_key.1 == start_r {
-                        path.push((start_q, start_r));
-                        break;
-                    }
-                    node_key = *parent_key;
-                } else {
-                    // No parent in map means we're at start (shouldn't happen in normal flow)
-                    // But handle it just in case
-                    if node_key.0 != start_q || node_key.1 != start_r {
-                        path.push((start_q, start_r));
-                    }
-                    break;
-                }
+        if current_key == (goal_q, goal_r) {
+            // Follow parent pointers back to the start (which has no parent entry)
+            let mut path = vec![current_key];
+            let mut node_key = current_key;
+            while let Some(&parent_key) = parents.get(&node_key) {
+                path.push(parent_key);
+                node_key = parent_key;
             }
-            
-            // Reverse path to get start-to-goal order
             path.reverse();
             
-            // Build JSON string
-            let mut json_parts = Vec::new();
-            for (q, r) in path {
-                json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
-            }
-            
-            return format!("[{}]", json_parts.join(","));
+            return (Some((path, current.g)), stats);
         }
         
         // Explore neighbors
-        let neighbors = get_hex_neighbors(current.q, current.r);
-        for (nq, nr) in neighbors {
-            let neighbor_key = (nq, nr);
-            
-            // Skip if not in valid terrain
-            if !valid_terrain.contains(&neighbor_key) {
-                continue;
-            }
-            
-            // Skip if already closed
+        for neighbor_key in get_hex_neighbors(current.q, current.r) {
+            // Skip impassable and already closed tiles
+            let cost = match step_cost(neighbor_key) {
+                Some(cost) => cost,
+                None => continue,
+            };
             if closed_set.contains(&neighbor_key) {
                 continue;
             }
             
-            // Calculate tentative g score (uniform cost of 1 per step)
-            let tentative_g = current.g + 1;
-            
             // Check if this is a better path
+            let tentative_g = current.g.saturating_add(cost);
             let current_g = g_scores.get(&neighbor_key).copied().unwrap_or(i32::MAX);
             if tentative_g < current_g {
                 // This path to neighbor is better - record it
                 g_scores.insert(neighbor_key, tentative_g);
-                parents.insert(neighbor_key, (current.q, current.r));
-                let h = heuristic(nq, nr);
-                open_set.push(AStarNode::new(nq, nr, tentative_g, h, current.q, current.r));
+                parents.insert(neighbor_key, current_key);
+                let h = heuristic(neighbor_key.0, neighbor_key.1);
+                let node = AStarNode::new(neighbor_key.0, neighbor_key.1, tentative_g, h, current.q, current.r);
+                if open_set.push_or_decrease(node) {
+                    stats.reopened += 1;
+                }
+                stats.max_open = stats.max_open.max(open_set.len());
             }
         }
     }
     
     // No path found
-    "null".to_string()
+    (None, stats)
 }
 
-/// Build a path between two road points using A* pathfinding
-/// Returns array of intermediate hexes (excluding start, including end)
-/// Matches TypeScript buildPathBetweenRoads function
+/// Weighted hex A* pathfinding over per-tile terrain costs
+/// 
+/// **Learning Point**: Same search as `hex_astar`, but stepping onto a tile adds that
+/// tile's cost to g instead of 1, so the result is the cheapest path rather than the
+/// shortest. Tiles missing from the cost map are impassable. The heuristic is hex
+/// distance times the cheapest cost in the map, which never overestimates and keeps
+/// the search optimal.
 /// 
 /// @param start_q - Start q coordinate (axial)
 /// @param start_r - Start r coordinate (axial)
-/// @param end_q - End q coordinate (axial)
-/// @param end_r - End r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param terrain_costs_json - JSON array of per-tile costs: [{"q":0,"r":0,"cost":3},...] (costs below 1 are impassable)
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
+#[wasm_bindgen]
+pub fn hex_astar_weighted(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    terrain_costs_json: String,
+) -> String {
+    let costs = parse_cost_json(&terrain_costs_json);
+    
+    let path = match weighted_astar_path(start_q, start_r, goal_q, goal_r, &costs) {
+        Some((path, _)) => path,
+        None => return "null".to_string(),
+    };
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Hex A* pathfinding around blocked tiles
+/// 
+/// **Learning Point**: Takes the full terrain once plus a set of blocked tiles, and
+/// subtracts them into the walkable HashSet internally. Callers no longer have to build
+/// and serialize `terrain minus occupied` themselves for every query.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param all_terrain_json - JSON array of all terrain hexes: [{"q":0,"r":0},...]
+/// @param blocked_json - JSON array of impassable hexes: [{"q":0,"r":0},...]
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
+#[wasm_bindgen]
+pub fn hex_astar_blocked(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    all_terrain_json: String,
+    blocked_json: String,
+) -> String {
+    astar_excluding(start_q, start_r, goal_q, goal_r, &all_terrain_json, &blocked_json)
+}
+
+/// Hex A* pathfinding that routes around soft no-go tiles
+/// 
+/// **Learning Point**: Same subtraction as `hex_astar_blocked`, meant for per-call
+/// preferences rather than hard obstacles: tiles that are valid terrain (existing
+/// buildings, a protected plaza) but that this particular route should stay out of.
+/// The avoided tiles are removed from the walkable set only for this search. If that
+/// cuts the start off from the goal (or the start or goal is itself avoided) the result
+/// is "null"; callers that prefer a path through the zone over no path can fall back to
+/// `hex_astar`.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param valid_terrain_json - JSON array of valid terrain hexes: [{"q":0,"r":0},...]
+/// @param avoid_json - JSON array of hexes to stay out of: [{"q":0,"r":0},...]
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
+#[wasm_bindgen]
+pub fn hex_astar_avoiding(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain_json: String,
+    avoid_json: String,
+) -> String {
+    astar_excluding(start_q, start_r, goal_q, goal_r, &valid_terrain_json, &avoid_json)
+}
+
+/// Hex A* over terrain minus excluded tiles, serialized like `hex_astar`
+fn astar_excluding(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    terrain_json: &str,
+    excluded_json: &str,
+) -> String {
+    let excluded = parse_valid_terrain_json(excluded_json);
+    let walkable: HashSet<(i32, i32)> = parse_coords(terrain_json)
+        .into_iter()
+        .filter(|hex| !excluded.contains(hex))
+        .collect();
+    
+    let path = match hex_astar_route(start_q, start_r, goal_q, goal_r, &walkable) {
+        Some(path) => path,
+        None => return "null".to_string(),
+    };
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Turn-penalty search state: (tile q, tile r, arrival direction); direction 6 marks the start
+type HeadingState = (i32, i32, usize);
+
+/// Hex A* pathfinding that penalizes changes of direction
+/// 
+/// **Learning Point**: With a uniform step cost every shortest path ties, and the search
+/// happily zig-zags between them. Here each step still costs 1, but a step whose
+/// direction differs from the previous one costs `turn_penalty` extra, so straighter
+/// routes win. Because the cost of the next step depends on how a tile was entered, the
+/// search state is (tile, arrival direction) rather than the tile alone: the same tile
+/// reached heading east and heading north-east are different nodes with separate g
+/// scores and separate closed-set entries. Closing a tile on its first arrival would
+/// throw away a straighter continuation that arrives slightly later. Cube distance
+/// stays an admissible heuristic since penalties only add cost. A penalty of 0 behaves
+/// like `hex_astar`.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
 /// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
-/// @returns JSON string with path array excluding start, including end, or "null" if no path found
+/// @param turn_penalty - Extra cost for each change of direction (negative values count as 0)
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
 #[wasm_bindgen]
-pub fn build_path_between_roads(
+pub fn hex_astar_smooth(
     start_q: i32,
     start_r: i32,
-    end_q: i32,
-    end_r: i32,
+    goal_q: i32,
+    goal_r: i32,
     valid_terrain_json: String,
+    turn_penalty: i32,
 ) -> String {
-    // Call hex_astar to get full path
-    let full_path_json = hex_astar(start_q, start_r, end_q, end_r, valid_terrain_json);
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
     
-    // If no path, return null
-    if full_path_json == "null" || full_path_json.is_empty() {
-        return "null".to_string();
+    let path = match smooth_astar_path(start_q, start_r, goal_q, goal_r, &valid_terrain, turn_penalty.max(0)) {
+        Some(path) => path,
+        None => return "null".to_string(),
+    };
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
     }
     
-    // Parse the path JSON
-    // Simple parsing: extract all {"q":X,"r":Y} patterns and skip first one
-    let trimmed = full_path_json.trim();
-    if trimmed == "[]" || trimmed.len() < 3 {
-        return "null".to_string();
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Turn-penalized A* over (tile, arrival direction) states
+/// Returns the path (start to goal, inclusive), or None if unreachable
+fn smooth_astar_path(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain: &HashSet<(i32, i32)>,
+    turn_penalty: i32,
+) -> Option<Vec<(i32, i32)>> {
+    if !valid_terrain.contains(&(start_q, start_r)) || !valid_terrain.contains(&(goal_q, goal_r)) {
+        return None;
     }
     
-    // Find all coordinate pairs
-    let mut coords: Vec<(i32, i32)> = Vec::new();
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
+    let goal_cube = axial_to_cube(goal_q, goal_r);
+    let heuristic = |q: i32, r: i32| -> i32 { cube_distance(axial_to_cube(q, r), goal_cube) };
+    
+    let start: HeadingState = (start_q, start_r, CUBE_DIRECTIONS.len());
+    
+    // Min-heap of (f, h, g, state); ties go to the smallest state, so results are deterministic
+    let mut open_set: BinaryHeap<Reverse<(i32, i32, i32, HeadingState)>> = BinaryHeap::new();
+    let mut closed_set: HashSet<HeadingState> = HashSet::new();
+    let mut g_scores: HashMap<HeadingState, i32> = HashMap::new();
+    let mut parents: HashMap<HeadingState, HeadingState> = HashMap::new();
+    
+    let h_start = heuristic(start_q, start_r);
+    open_set.push(Reverse((h_start, h_start, 0, start)));
+    g_scores.insert(start, 0);
+    
+    while let Some(Reverse((_, _, g, state))) = open_set.pop() {
+        // Skip if already processed (duplicate in open_set)
+        if !closed_set.insert(state) {
+            continue;
+        }
+        
+        let (q, r, heading) = state;
+        if q == goal_q && r == goal_r {
+            // Follow parent pointers back to the start
+            let mut path = vec![(q, r)];
+            let mut node = state;
+            while let Some(&parent) = parents.get(&node) {
+                path.push((parent.0, parent.1));
+                node = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        
+        for (direction, offset) in CUBE_DIRECTIONS.iter().enumerate() {
+            let (nq, nr) = (q + offset.q, r + offset.r);
+            if !valid_terrain.contains(&(nq, nr)) {
+                continue;
+            }
             
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
+            let neighbor: HeadingState = (nq, nr, direction);
+            if closed_set.contains(&neighbor) {
+                continue;
+            }
+            
+            // The first step from the start has no previous direction to turn from
+            let turn_cost = if heading != direction && heading != CUBE_DIRECTIONS.len() { turn_penalty } else { 0 };
+            let tentative_g = g.saturating_add(1).saturating_add(turn_cost);
+            
+            let current_g = g_scores.get(&neighbor).copied().unwrap_or(i32::MAX);
+            if tentative_g < current_g {
+                g_scores.insert(neighbor, tentative_g);
+                parents.insert(neighbor, state);
+                let h = heuristic(nq, nr);
+                open_set.push(Reverse((tentative_g.saturating_add(h), h, tentative_g, neighbor)));
+            }
+        }
+    }
+    
+    None
+}
+
+/// One side of a bidirectional search: visited tile -> (parent, depth); the root is its own parent
+type SearchTree = HashMap<(i32, i32), ((i32, i32), i32)>;
+
+/// Meeting point of a bidirectional search: (total path length in steps, tile)
+type Meeting = (i32, (i32, i32));
+
+/// Expand one full layer of a bidirectional search and report the best meeting point
+/// Returns the next layer and, if this layer touched the other side, the meeting tile
+/// with the lowest total path length (ties go to the smallest (q, r))
+fn expand_layer(
+    layer: &[(i32, i32)],
+    walkable: &HashSet<(i32, i32)>,
+    own: &mut SearchTree,
+    other: &SearchTree,
+) -> (Vec<(i32, i32)>, Option<Meeting>) {
+    let mut next = Vec::new();
+    let mut meeting: Option<Meeting> = None;
+    
+    for &current in layer {
+        let depth = own[&current].1;
+        for neighbor in get_hex_neighbors(current.0, current.1) {
+            if !walkable.contains(&neighbor) || own.contains_key(&neighbor) {
+                continue;
             }
+            own.insert(neighbor, (current, depth + 1));
+            next.push(neighbor);
             
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                coords.push((q, r));
+            if let Some(&(_, other_depth)) = other.get(&neighbor) {
+                let candidate = (depth + 1 + other_depth, neighbor);
+                if meeting.is_none_or(|best| candidate < best) {
+                    meeting = Some(candidate);
+                }
             }
         }
-        i += 1;
     }
     
+    (next, meeting)
+}
+
+/// Bidirectional hex pathfinding for long paths
+/// 
+/// **Learning Point**: Two searches grow at once, one from the start and one from the
+/// goal, and stop when their frontiers meet. With uniform step costs each side expands a
+/// whole layer (all tiles at the same depth) at a time, always advancing the smaller
+/// frontier. The first layer that reaches the other side contains a shortest meeting
+/// point, so the stitched path has the same length as `hex_astar`'s. On open maps each
+/// side only explores a disc of about half the path length, roughly half the tiles
+/// a one-sided search visits.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
+/// @returns JSON string with path array [{"q":0,"r":0},...] or "null" if no path found
+#[wasm_bindgen]
+pub fn hex_astar_bidirectional(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain_json: String,
+) -> String {
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    let start = (start_q, start_r);
+    let goal = (goal_q, goal_r);
+    
+    if !valid_terrain.contains(&start) || !valid_terrain.contains(&goal) {
+        return "null".to_string();
+    }
+    
+    let mut forward: SearchTree = HashMap::new();
+    let mut backward: SearchTree = HashMap::new();
+    forward.insert(start, (start, 0));
+    backward.insert(goal, (goal, 0));
+    
+    let mut meeting = if start == goal { Some(start) } else { None };
+    let mut forward_layer = vec![start];
+    let mut backward_layer = vec![goal];
+    
+    while meeting.is_none() && !forward_layer.is_empty() && !backward_layer.is_empty() {
+        let found = if forward_layer.len() <= backward_layer.len() {
+            let (next, found) = expand_layer(&forward_layer, &valid_terrain, &mut forward, &backward);
+            forward_layer = next;
+            found
+        } else {
+            let (next, found) = expand_layer(&backward_layer, &valid_terrain, &mut backward, &forward);
+            backward_layer = next;
+            found
+        };
+        meeting = found.map(|(_, tile)| tile);
+    }
+    
+    let meeting = match meeting {
+        Some(tile) => tile,
+        None => return "null".to_string(),
+    };
+    
+    // Stitch: start -> meeting from the forward parents, then meeting -> goal from the backward parents
+    let mut path = vec![meeting];
+    let mut node = meeting;
+    while node != start {
+        node = forward[&node].0;
+        path.push(node);
+    }
+    path.reverse();
+    
+    let mut node = meeting;
+    while node != goal {
+        node = backward[&node].0;
+        path.push(node);
+    }
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Hex A* pathfinding that also reports the path cost and search effort
+/// 
+/// **Learning Point**: Same search as `hex_astar`. `cost` is the g score at the goal
+/// (steps, since every step costs 1) so two candidate routes can be compared without
+/// re-summing them in JS. `expanded` counts the nodes moved to the closed set - a high
+/// count relative to `cost` means the heuristic is being fooled, typically by walls
+/// between start and goal, and is a good signal when profiling slow maps.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
+/// @returns JSON object {"path":[{"q":0,"r":0},...],"cost":7,"expanded":42} or "null" if no path found
+#[wasm_bindgen]
+pub fn hex_astar_with_cost(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain_json: String,
+) -> String {
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let (path, stats) = uniform_astar_search(start_q, start_r, goal_q, goal_r, &valid_terrain);
+    let path = match path {
+        Some(path) => path,
+        None => return "null".to_string(),
+    };
+    
+    let cost = path.len() - 1;
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!(r#"{{"path":[{}],"cost":{},"expanded":{}}}"#, json_parts.join(","), cost, stats.expanded)
+}
+
+/// Hex A* search effort statistics
+/// 
+/// **Learning Point**: Runs the same search as `hex_astar` but reports only how hard it
+/// worked, for profiling map generation. `expanded` counts closed-set insertions,
+/// `max_open` is the peak open-set size and `reopened` counts how often a node already
+/// waiting in the open set found a better g. The open set is an indexed heap that
+/// updates such a node in place, so `max_open` counts distinct queued tiles only.
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param goal_q - Goal q coordinate (axial)
+/// @param goal_r - Goal r coordinate (axial)
+/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
+/// @returns JSON object {"path_len":7,"expanded":42,"max_open":18,"reopened":0}; path_len is the step count, or -1 if no path found
+#[wasm_bindgen]
+pub fn hex_astar_stats(
+    start_q: i32,
+    start_r: i32,
+    goal_q: i32,
+    goal_r: i32,
+    valid_terrain_json: String,
+) -> String {
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let (path, stats) = uniform_astar_search(start_q, start_r, goal_q, goal_r, &valid_terrain);
+    let path_len = path.map_or(-1, |path| path.len() as i64 - 1);
+    
+    format!(
+        r#"{{"path_len":{},"expanded":{},"max_open":{},"reopened":{}}}"#,
+        path_len, stats.expanded, stats.max_open, stats.reopened
+    )
+}
+
+/// Build a path between two road points using A* pathfinding
+/// Returns array of intermediate hexes (excluding start, including end)
+/// Matches TypeScript buildPathBetweenRoads function
+/// 
+/// @param start_q - Start q coordinate (axial)
+/// @param start_r - Start r coordinate (axial)
+/// @param end_q - End q coordinate (axial)
+/// @param end_r - End r coordinate (axial)
+/// @param valid_terrain_json - JSON string with array of valid terrain coordinates: [{"q":0,"r":0},...]
+/// @returns JSON string with path array excluding start, including end, or "null" if no path found
+#[wasm_bindgen]
+pub fn build_path_between_roads(
+    start_q: i32,
+    start_r: i32,
+    end_q: i32,
+    end_r: i32,
+    valid_terrain_json: String,
+) -> String {
+    // Run A* to get the full path
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    let coords = match hex_astar_route(start_q, start_r, end_q, end_r, &valid_terrain) {
+        Some(path) => path,
+        None => return "null".to_string(),
+    };
+    
     // If path has less than 2 nodes, return null
     if coords.len() < 2 {
         return "null".to_string();
@@ -343,75 +990,7 @@ pub fn build_path_between_roads(
 #[wasm_bindgen]
 pub fn validate_road_connectivity(roads_json: String) -> bool {
     // Parse roads from JSON
-    // Simple JSON parsing without serde to keep WASM size small
-    let mut roads: Vec<(i32, i32)> = Vec::new();
-    
-    // Remove whitespace and brackets
-    let trimmed = roads_json.trim();
-    if trimmed.is_empty() || trimmed == "[]" {
-        return true; // Empty roads is trivially connected
-    }
-
-    // Simple JSON parsing: find all {"q":X,"r":Y} patterns
-    // This is a simplified parser that handles the expected format: [{"q":0,"r":0},...]
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        // Look for opening brace
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                // Look for "q" or "r" followed by colon and number
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    // Skip colon and whitespace
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    // Parse number
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    // Skip colon and whitespace
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    // Parse number
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
-            }
-            
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                roads.push((q, r));
-            }
-        }
-        i += 1;
-    }
+    let roads = parse_coords(&roads_json);
 
     if roads.is_empty() {
         return true;
@@ -440,3 +1019,305 @@ pub fn validate_road_connectivity(roads_json: String) -> bool {
     true // All roads reachable from source
 }
 
+
+/// Compute shortest path lengths between every pair of a small set of points
+/// 
+/// **Learning Point**: Running A* for every pair costs N² searches. One full
+/// single-source search per point (N searches) yields the same matrix, since each
+/// search already measures the distance to every other point. Steps cost 1, so the
+/// single-source search is a plain BFS. The result is the input for tour planning
+/// (TSP-style ordering of waypoints).
+/// 
+/// @param points_json - JSON array of points: [{"q":0,"r":0},...] (at most 64, order preserved)
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @returns JSON NxN matrix of path lengths in steps: [[0,3],[3,0]], where -1 means
+///          unreachable and the diagonal is 0, or "null" if more than 64 points are given
+#[wasm_bindgen]
+pub fn hex_distance_matrix(points_json: String, valid_terrain_json: String) -> String {
+    let points = parse_path_json(&points_json);
+    if points.len() > MAX_MATRIX_POINTS {
+        return "null".to_string();
+    }
+    
+    let walkable = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let mut row_parts = Vec::new();
+    for (i, &(q, r)) in points.iter().enumerate() {
+        let distances = hex_bfs_distances(q, r, &walkable);
+        let values: Vec<String> = points.iter()
+            .enumerate()
+            .map(|(j, point)| {
+                if i == j {
+                    0
+                } else {
+                    distances.get(point).copied().unwrap_or(-1)
+                }
+            })
+            .map(|distance| distance.to_string())
+            .collect();
+        row_parts.push(format!("[{}]", values.join(",")));
+    }
+    
+    format!("[{}]", row_parts.join(","))
+}
+
+/// Detect whether a path visits any hex more than once
+/// 
+/// **Learning Point**: Stitching A* segments together can produce a path that loops
+/// back over itself. Walking the path while remembering visited hexes in a HashSet finds
+/// the first revisit in O(n). `first_repeat` is the hex at the earliest point where the
+/// path steps onto a tile it already visited.
+/// 
+/// @param path_json - JSON array of path hexes in order: [{"q":0,"r":0},...]
+/// @returns JSON object: {"self_intersects":true,"first_repeat":{"q":0,"r":0}},
+///          or {"self_intersects":false,"first_repeat":null}
+#[wasm_bindgen]
+pub fn path_self_intersects(path_json: String) -> String {
+    let path = parse_path_json(&path_json);
+    
+    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    for (q, r) in path {
+        if !visited.insert((q, r)) {
+            return format!(
+                r#"{{"self_intersects":true,"first_repeat":{{"q":{},"r":{}}}}}"#,
+                q, r
+            );
+        }
+    }
+    
+    r#"{"self_intersects":false,"first_repeat":null}"#.to_string()
+}
+
+/// Route a path through waypoints in a fixed order
+/// 
+/// **Learning Point**: Each consecutive pair of waypoints is one A* leg. Legs share an
+/// endpoint, so every leg after the first drops its start tile when appended - the
+/// waypoint is already the last tile of the previous leg. Unlike a tour the order is
+/// never changed and the path does not return to the first waypoint.
+/// 
+/// @param waypoints_json - JSON array of waypoints in visiting order: [{"q":0,"r":0},...]
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @returns JSON path array [{"q":0,"r":0},...], "[]" for no waypoints,
+///          or "null" if any leg is unreachable
+#[wasm_bindgen]
+pub fn hex_path_via(waypoints_json: String, valid_terrain_json: String) -> String {
+    let waypoints = parse_path_json(&waypoints_json);
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let mut full_path: Vec<(i32, i32)> = Vec::new();
+    if let Some(&(q, r)) = waypoints.first() {
+        if !valid_terrain.contains(&(q, r)) {
+            return "null".to_string();
+        }
+        full_path.push((q, r));
+    }
+    
+    for leg in waypoints.windows(2) {
+        let (start, goal) = (leg[0], leg[1]);
+        match hex_astar_route(start.0, start.1, goal.0, goal.1, &valid_terrain) {
+            Some(path) => full_path.extend(path.into_iter().skip(1)),
+            None => return "null".to_string(),
+        }
+    }
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in full_path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Split road tiles into connected components
+/// 
+/// **Learning Point**: Where `validate_road_connectivity` only answers yes/no, this
+/// labels every road with its component via BFS over neighboring roads. The pieces
+/// show where the network is broken and which islands need stitching. Components are
+/// ordered by their smallest (q, r) tile and tiles within a component are sorted, so a
+/// connected network comes back as a single group holding every road.
+/// 
+/// @param roads_json - JSON array of road coordinates: [{"q":0,"r":0},...]
+/// @returns JSON array: [{"component":0,"tiles":[{"q":0,"r":0},...]},...]
+#[wasm_bindgen]
+pub fn road_components_json(roads_json: String) -> String {
+    let roads = parse_valid_terrain_json(&roads_json);
+    
+    let mut json_parts = Vec::new();
+    for (component, tiles) in connected_components(&roads).into_iter().enumerate() {
+        let tile_parts: Vec<String> = tiles.iter()
+            .map(|(q, r)| format!(r#"{{"q":{},"r":{}}}"#, q, r))
+            .collect();
+        json_parts.push(format!(
+            r#"{{"component":{},"tiles":[{}]}}"#,
+            component, tile_parts.join(",")
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{coords_json, disk};
+    
+    #[test]
+    fn hex_astar_rejects_truncated_terrain() {
+        assert_eq!(hex_astar(0, 0, 1, 0, "[{\"q".to_string()), "null");
+    }
+    
+    #[test]
+    fn distance_matrix_is_symmetric_with_zero_diagonal() {
+        // A wall at q = 0 (open at r = 3) makes the distances differ from hex_distance
+        let terrain: Vec<(i32, i32)> = disk(3).into_iter().filter(|&(q, r)| q != 0 || r == 3).collect();
+        let points = [(-2, 0), (2, 0), (0, 3)];
+        let matrix: Vec<Vec<i32>> = serde_json::from_str(&hex_distance_matrix(coords_json(&points), coords_json(&terrain))).unwrap();
+        
+        assert_eq!(matrix.len(), 3);
+        for (i, row) in matrix.iter().enumerate() {
+            assert_eq!(row[i], 0);
+            for (j, &distance) in row.iter().enumerate() {
+                assert_eq!(distance, matrix[j][i]);
+                assert!(i == j || distance > 0);
+            }
+        }
+        assert!(matrix[0][1] > hex_distance(-2, 0, 2, 0));
+    }
+    
+    #[test]
+    fn figure_eight_path_intersects_itself() {
+        // Loop around (0,0) through (1,-1), then loop around (2,-1) back through (1,-1)
+        let figure_eight = [(1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1), (2, -2), (3, -2), (3, -1), (2, 0), (1, 0)];
+        assert_eq!(
+            path_self_intersects(coords_json(&figure_eight)),
+            r#"{"self_intersects":true,"first_repeat":{"q":1,"r":-1}}"#
+        );
+        
+        let simple = [(0, 0), (1, 0), (2, -1), (3, -1)];
+        assert_eq!(path_self_intersects(coords_json(&simple)), r#"{"self_intersects":false,"first_repeat":null}"#);
+    }
+    
+    #[test]
+    fn path_via_visits_waypoints_in_order() {
+        let terrain = disk(4);
+        let waypoints = [(-3, 0), (0, 3), (3, -3)];
+        let path = parse_path_json(&hex_path_via(coords_json(&waypoints), coords_json(&terrain)));
+        
+        // Each step moves to a neighbor, and the waypoints appear in visiting order
+        assert!(path.windows(2).all(|step| hex_distance(step[0].0, step[0].1, step[1].0, step[1].1) == 1));
+        let positions: Vec<usize> = waypoints.iter()
+            .map(|waypoint| path.iter().position(|hex| hex == waypoint).unwrap())
+            .collect();
+        assert_eq!(positions[0], 0);
+        assert!(positions[0] < positions[1] && positions[1] < positions[2]);
+        assert_eq!(positions[2], path.len() - 1);
+    }
+    
+    #[test]
+    fn bidirectional_agrees_with_astar_on_random_terrain() {
+        let mut found = 0;
+        for seed in 0..8 {
+            // Drop about a third of a radius-6 disk as obstacles
+            let terrain: Vec<(i32, i32)> = disk(6).into_iter()
+                .filter(|&(q, r)| crate::utils::coord_random(seed, q, r) >= 0.35 || (q, r) == (-5, 2) || (q, r) == (5, -2))
+                .collect();
+            let terrain_json = coords_json(&terrain);
+            
+            let forward = hex_astar(-5, 2, 5, -2, terrain_json.clone());
+            let bidirectional = hex_astar_bidirectional(-5, 2, 5, -2, terrain_json);
+            assert_eq!(forward == "null", bidirectional == "null", "seed {}", seed);
+            if forward != "null" {
+                found += 1;
+                let path = parse_path_json(&bidirectional);
+                assert_eq!(path.len(), parse_path_json(&forward).len(), "seed {}", seed);
+                assert_eq!((path[0], path[path.len() - 1]), ((-5, 2), (5, -2)));
+                assert!(path.windows(2).all(|step| hex_distance(step[0].0, step[0].1, step[1].0, step[1].1) == 1));
+                assert!(path.iter().all(|hex| terrain.contains(hex)));
+            }
+        }
+        assert!(found >= 4, "only {} of 8 terrains had a path", found);
+    }
+    
+    /// Lazy-deletion A* (a duplicate heap entry per improvement, stale ones skipped on
+    /// pop), as the search worked before the indexed heap. Returns its cost and peak heap size.
+    fn lazy_astar_peak_heap(
+        start: (i32, i32),
+        goal: (i32, i32),
+        step_cost: impl Fn((i32, i32)) -> Option<i32>,
+    ) -> (Option<i32>, usize) {
+        let heuristic = |key: (i32, i32)| hex_distance(key.0, key.1, goal.0, goal.1);
+        let mut open = BinaryHeap::new();
+        let mut g_scores: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut closed = HashSet::new();
+        open.push(AStarNode::new(start.0, start.1, 0, heuristic(start), start.0, start.1));
+        g_scores.insert(start, 0);
+        let mut peak = 1;
+        
+        while let Some(current) = open.pop() {
+            let key = (current.q, current.r);
+            if !closed.insert(key) {
+                continue;
+            }
+            if key == goal {
+                return (Some(current.g), peak);
+            }
+            for neighbor in get_hex_neighbors(key.0, key.1) {
+                let Some(cost) = step_cost(neighbor) else { continue };
+                let tentative_g = current.g + cost;
+                if !closed.contains(&neighbor) && tentative_g < g_scores.get(&neighbor).copied().unwrap_or(i32::MAX) {
+                    g_scores.insert(neighbor, tentative_g);
+                    open.push(AStarNode::new(neighbor.0, neighbor.1, tentative_g, heuristic(neighbor), key.0, key.1));
+                    peak = peak.max(open.len());
+                }
+            }
+        }
+        (None, peak)
+    }
+    
+    #[test]
+    fn indexed_heap_keeps_peak_open_set_small_on_40_layer_grid() {
+        // Cheap west half, expensive east half: the cheap detour keeps improving tiles the
+        // expensive frontier already queued, which left stale duplicates in the lazy heap
+        let terrain: HashSet<(i32, i32)> = disk(40).into_iter().collect();
+        let step_cost = |key: (i32, i32)| terrain.contains(&key).then_some(if key.0 < 0 { 1 } else { 9 });
+        
+        let (result, stats) = hex_astar_search(-20, 40, 20, -40, step_cost, 1);
+        let (lazy_cost, lazy_peak) = lazy_astar_peak_heap((-20, 40), (20, -40), step_cost);
+        
+        assert_eq!(result.map(|(_, cost)| cost), lazy_cost);
+        assert!(stats.reopened > 1000, "expected heavy reopening, got {}", stats.reopened);
+        assert!(stats.max_open * 4 < lazy_peak, "indexed peak {} vs lazy peak {}", stats.max_open, lazy_peak);
+    }
+    
+    fn count_turns(path: &[(i32, i32)]) -> usize {
+        let steps: Vec<(i32, i32)> = path.windows(2).map(|w| (w[1].0 - w[0].0, w[1].1 - w[0].1)).collect();
+        steps.windows(2).filter(|pair| pair[0] != pair[1]).count()
+    }
+    
+    #[test]
+    fn high_turn_penalty_prefers_straighter_paths() {
+        let mut compared = 0;
+        for seed in 0..10 {
+            // Scattered rocks force the shortest route to weave between them
+            let terrain: Vec<(i32, i32)> = disk(8).into_iter()
+                .filter(|&(q, r)| crate::utils::coord_random(seed, q, r) > 0.25 || (q, r) == (-6, 0) || (q, r) == (6, -3))
+                .collect();
+            let terrain_json = coords_json(&terrain);
+            let plain = hex_astar_smooth(-6, 0, 6, -3, terrain_json.clone(), 0);
+            if plain == "null" {
+                continue;
+            }
+            let plain = parse_path_json(&plain);
+            let smooth = parse_path_json(&hex_astar_smooth(-6, 0, 6, -3, terrain_json, 10));
+            compared += 1;
+            
+            assert_eq!((smooth[0], smooth[smooth.len() - 1]), ((-6, 0), (6, -3)));
+            assert!(smooth.windows(2).all(|step| hex_distance(step[0].0, step[0].1, step[1].0, step[1].1) == 1));
+            assert!(smooth.iter().all(|hex| terrain.contains(hex)));
+            // Straighter may mean longer, never shorter than the penalty-free path
+            assert!(smooth.len() >= plain.len(), "seed {}", seed);
+            assert!(count_turns(&smooth) < count_turns(&plain), "seed {}: {} vs {} turns", seed, count_turns(&smooth), count_turns(&plain));
+        }
+        assert!(compared >= 5, "only {} of 10 terrains had a path", compared);
+    }
+}
@@ -1,7 +1,7 @@
 /// Chunk management module
 
 use wasm_bindgen::prelude::*;
-use crate::hex_utils::{parse_valid_terrain_json, hex_distance};
+use crate::hex_utils::{parse_valid_terrain_json, parse_int_fields_json, hex_distance};
 
 /// Calculate chunk radius for distance threshold calculations
 /// The chunk radius is the distance from chunk center to the outer boundary
@@ -139,88 +139,17 @@ pub fn disable_distant_chunks(
     all_chunks_json: String,
     max_distance: i32,
 ) -> String {
-    // Parse chunks with enabled state
-    // Format: [{"q":0,"r":0,"enabled":true},...]
-    let mut chunks: Vec<(i32, i32, bool)> = Vec::new();
-    
     let trimmed = all_chunks_json.trim();
     if trimmed.is_empty() || trimmed == "[]" {
         return r#"{"toDisable":[],"toEnable":[]}"#.to_string();
     }
     
-    // Simple JSON parsing: find all {"q":X,"r":Y,"enabled":Z} patterns
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            let mut enabled_value: Option<bool> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                // Look for "q", "r", or "enabled"
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else if i + 9 < chars.len() && chars[i] == '"' && chars[i + 1] == 'e' && chars[i + 2] == 'n' 
-                    && chars[i + 3] == 'a' && chars[i + 4] == 'b' && chars[i + 5] == 'l' 
-                    && chars[i + 6] == 'e' && chars[i + 7] == 'd' && chars[i + 8] == '"' {
-                    i += 9;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() {
-                        if i + 4 < chars.len() && chars[i] == 't' && chars[i + 1] == 'r' 
-                            && chars[i + 2] == 'u' && chars[i + 3] == 'e' {
-                            enabled_value = Some(true);
-                            i += 4;
-                        } else if i + 5 < chars.len() && chars[i] == 'f' && chars[i + 1] == 'a' 
-                            && chars[i + 2] == 'l' && chars[i + 3] == 's' && chars[i + 4] == 'e' {
-                            enabled_value = Some(false);
-                            i += 5;
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
-            }
-            
-            if let (Some(q), Some(r), Some(enabled)) = (q_value, r_value, enabled_value) {
-                chunks.push((q, r, enabled));
-            }
-        }
-        i += 1;
-    }
+    // Parse chunks with enabled state
+    // Format: [{"q":0,"r":0,"enabled":true},...]
+    let chunks: Vec<(i32, i32, bool)> = parse_int_fields_json(trimmed, &["q", "r", "enabled"])
+        .into_iter()
+        .map(|fields| (fields[0], fields[1], fields[2] != 0))
+        .collect();
     
     // Calculate which chunks to disable/enable
     let mut to_disable: Vec<(i32, i32)> = Vec::new();
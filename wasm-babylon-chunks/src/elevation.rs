@@ -0,0 +1,339 @@
+//! Elevation analysis module
+//!
+//! Functions here take an elevation map as JSON (`[{"q":0,"r":0,"elev":12},...]`)
+//! with integer elevations and derive drainage and steepness information from it.
+//! `generate_terrain_from_elevation` goes the other way, turning a seeded noise
+//! elevation field into terrain.
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashMap;
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, parse_elevation_json, parse_valid_terrain_json, get_hex_neighbors, tile_map_to_json};
+use crate::utils::coord_random;
+
+/// Value-noise octaves as (lattice spacing in hexes, weight); the weights sum to 1
+const NOISE_OCTAVES: [(i32, f64); 3] = [(8, 0.55), (4, 0.3), (2, 0.15)];
+
+/// Upper elevation bound of each band, lowest first; anything above is mountain
+const ELEVATION_BANDS: [(f64, TileType); 4] = [
+    (0.4, TileType::Water),
+    (0.45, TileType::Sand),
+    (0.58, TileType::Grass),
+    (0.68, TileType::Forest),
+];
+
+/// Sample one octave of value noise at an axial coordinate
+/// Random values sit on a lattice every `spacing` hexes along q and r; values in
+/// between are interpolated bilinearly with a smoothstep fade, so there are no creases
+/// at lattice lines
+fn value_noise(seed: u32, q: i32, r: i32, spacing: i32) -> f64 {
+    let (lq, lr) = (q.div_euclid(spacing), r.div_euclid(spacing));
+    let fade = |t: f64| t * t * (3.0 - 2.0 * t);
+    let tq = fade(q.rem_euclid(spacing) as f64 / spacing as f64);
+    let tr = fade(r.rem_euclid(spacing) as f64 / spacing as f64);
+    
+    let top = coord_random(seed, lq, lr) * (1.0 - tq) + coord_random(seed, lq + 1, lr) * tq;
+    let bottom = coord_random(seed, lq, lr + 1) * (1.0 - tq) + coord_random(seed, lq + 1, lr + 1) * tq;
+    top * (1.0 - tr) + bottom * tr
+}
+
+/// Elevation in [0, 1) at an axial coordinate: the weighted sum of `NOISE_OCTAVES`,
+/// each octave drawing from its own seed
+fn noise_elevation(seed: u32, q: i32, r: i32) -> f64 {
+    NOISE_OCTAVES.iter()
+        .enumerate()
+        .map(|(octave, &(spacing, weight))| {
+            weight * value_noise(seed.wrapping_add(octave as u32), q, r, spacing)
+        })
+        .sum()
+}
+
+/// Find the tile one tile drains into
+/// The target is the lowest neighbor that is strictly lower than the tile; ties go to
+/// the first neighbor in `CUBE_DIRECTIONS` order. Sinks (no lower neighbor) map to themselves.
+fn flow_target(elevations: &HashMap<(i32, i32), i32>, q: i32, r: i32, elev: i32) -> (i32, i32) {
+    let mut target = (q, r);
+    let mut lowest = elev;
+    for neighbor in get_hex_neighbors(q, r) {
+        if let Some(&neighbor_elev) = elevations.get(&neighbor) {
+            if neighbor_elev < lowest {
+                lowest = neighbor_elev;
+                target = neighbor;
+            }
+        }
+    }
+    target
+}
+
+/// Find the tile each tile drains into (see `flow_target`)
+/// Because every step goes strictly downhill, the flow graph never has cycles.
+fn flow_targets(elevations: &HashMap<(i32, i32), i32>) -> HashMap<(i32, i32), (i32, i32)> {
+    elevations.iter()
+        .map(|(&(q, r), &elev)| ((q, r), flow_target(elevations, q, r, elev)))
+        .collect()
+}
+
+/// Compute the downhill flow direction of every tile
+/// 
+/// **Learning Point**: This is the hex-grid analog of the D8 flow model used in
+/// terrain analysis: each tile drains into its lowest neighbor, as long as that neighbor
+/// is strictly lower. Tiles with no lower neighbor (pits, flat areas, the lowest edge
+/// of the map) are sinks and flow to themselves. Following `flows_to` from any tile
+/// always ends at a sink, which is how rivers are traced from source to mouth.
+/// 
+/// @param elevation_json - JSON elevation map: [{"q":0,"r":0,"elev":12},...]
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"flows_to":{"q":1,"r":0}},...]
+#[wasm_bindgen]
+pub fn compute_flow_directions(elevation_json: String) -> String {
+    let elevations = parse_elevation_json(&elevation_json);
+    
+    let mut flows: Vec<((i32, i32), (i32, i32))> = flow_targets(&elevations).into_iter().collect();
+    flows.sort();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), (tq, tr)) in flows {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"flows_to":{{"q":{},"r":{}}}}}"#,
+            q, r, tq, tr
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Trace rivers by accumulating flow along the downhill flow directions
+/// 
+/// **Learning Point**: Flow accumulation counts how many tiles drain through each tile,
+/// the tile itself included - so every tile starts at 1. Flow always goes strictly
+/// downhill, which means visiting tiles from highest to lowest elevation is a valid
+/// topological order: by the time a tile is visited, everything upstream of it has
+/// already passed its count down. Each tile then adds its total to its flow target.
+/// Sinks keep what they collect. Tiles whose accumulation exceeds `flow_threshold`
+/// form a branching river network that widens downstream.
+/// 
+/// @param elevation_json - JSON elevation map: [{"q":0,"r":0,"elev":12},...]
+/// @param flow_threshold - Minimum accumulation (exclusive) for a tile to be river
+/// @returns JSON array of river tiles sorted by (q, r): [{"q":0,"r":0,"accumulation":A},...]
+#[wasm_bindgen]
+pub fn trace_rivers(elevation_json: String, flow_threshold: i32) -> String {
+    let elevations = parse_elevation_json(&elevation_json);
+    let targets = flow_targets(&elevations);
+    
+    let mut order: Vec<((i32, i32), i32)> = elevations.iter().map(|(&key, &elev)| (key, elev)).collect();
+    order.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    
+    let mut accumulation: HashMap<(i32, i32), i32> = elevations.keys().map(|&key| (key, 1)).collect();
+    for (key, _) in &order {
+        let target = targets[key];
+        if target != *key {
+            let amount = accumulation[key];
+            if let Some(downstream) = accumulation.get_mut(&target) {
+                *downstream += amount;
+            }
+        }
+    }
+    
+    let mut rivers: Vec<((i32, i32), i32)> = accumulation.into_iter()
+        .filter(|&(_, amount)| amount > flow_threshold)
+        .collect();
+    rivers.sort();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), amount) in rivers {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"accumulation":{}}}"#, q, r, amount));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Trace a single river downhill from a starting tile
+/// 
+/// **Learning Point**: Where `trace_rivers` finds every river at once, this follows one
+/// drop of water: from the start it repeatedly steps to the lowest strictly lower
+/// neighbor (the same rule as `compute_flow_directions`). It stops on reaching one of
+/// the given water tiles, or at a local minimum where no neighbor is lower. Every
+/// step goes strictly downhill, so the walk can never revisit a tile and always ends.
+/// Neighbors missing from the elevation map are never stepped onto.
+/// 
+/// @param start_q - Source q coordinate (usually a high tile, e.g. a mountain)
+/// @param start_r - Source r coordinate
+/// @param elevation_json - JSON elevation map: [{"q":0,"r":0,"elev":12},...]
+/// @param water_json - JSON array of water tiles the river may end in: [{"q":0,"r":0},...]
+/// @returns JSON array of the river path from source to mouth: [{"q":0,"r":0},...],
+///          or "[]" if the start is not in the elevation map
+#[wasm_bindgen]
+pub fn generate_river(start_q: i32, start_r: i32, elevation_json: String, water_json: String) -> String {
+    let elevations = parse_elevation_json(&elevation_json);
+    let water = parse_valid_terrain_json(&water_json);
+    
+    let mut path = Vec::new();
+    let mut current = (start_q, start_r);
+    while let Some(&elev) = elevations.get(&current) {
+        path.push(current);
+        if water.contains(&current) {
+            break;
+        }
+        
+        let next = flow_target(&elevations, current.0, current.1, elev);
+        if next == current {
+            break;
+        }
+        current = next;
+    }
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in path {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Compute the steepness of every tile
+/// 
+/// **Learning Point**: Slope is the largest absolute elevation difference between a
+/// tile and any of its neighbors. Neighbors missing from the map are ignored, so a tile
+/// with no neighbors in the map has slope 0. Tiles on either side of a cliff both report
+/// the cliff height, which is what cliff rendering and slope-based movement costs need.
+/// 
+/// @param elevation_json - JSON elevation map: [{"q":0,"r":0,"elev":12},...]
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"slope":S},...]
+#[wasm_bindgen]
+pub fn compute_slopes(elevation_json: String) -> String {
+    let elevations = parse_elevation_json(&elevation_json);
+    
+    let mut slopes: Vec<((i32, i32), i64)> = elevations.iter()
+        .map(|(&(q, r), &elev)| {
+            let slope = get_hex_neighbors(q, r)
+                .iter()
+                .filter_map(|neighbor| elevations.get(neighbor))
+                .map(|&neighbor_elev| (elev as i64 - neighbor_elev as i64).abs())
+                .max()
+                .unwrap_or(0);
+            ((q, r), slope)
+        })
+        .collect();
+    slopes.sort();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), slope) in slopes {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"slope":{}}}"#, q, r, slope));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Generate terrain from a seeded noise elevation field
+/// 
+/// **Learning Point**: Voronoi regions are blobs with no relation to each other. Here
+/// every hex gets an elevation from layered value noise (`NOISE_OCTAVES`: coarse
+/// octaves shape continents, fine octaves roughen their coasts), and terrain follows
+/// height in bands: water, then sand, grass, forest, and mountain at the top
+/// (`ELEVATION_BANDS`). Beaches therefore ring the water and mountains rise out of
+/// forest. The noise is sampled at world coordinates, so chunks generated with the same
+/// seed line up at their borders.
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param seed - Seed for the elevation field
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"tileType":4},...], ready for
+///          `set_pre_constraints_json`; "[]" if max_layer is negative
+#[wasm_bindgen]
+pub fn generate_terrain_from_elevation(max_layer: i32, center_q: i32, center_r: i32, seed: u32) -> String {
+    let mut tiles = HashMap::new();
+    
+    for hex in generate_hex_grid(max_layer, center_q, center_r) {
+        let elevation = noise_elevation(seed, hex.q, hex.r);
+        let tile_type = ELEVATION_BANDS.iter()
+            .find(|&&(upper, _)| elevation < upper)
+            .map_or(TileType::Mountain, |&(_, band)| band);
+        tiles.insert((hex.q, hex.r), tile_type);
+    }
+    
+    tile_map_to_json(&tiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_utils::parse_int_fields_json;
+    
+    /// A row of tiles along q at r = 0 with the given elevations, starting at q = 0
+    fn row_json(elevations: &[i32]) -> String {
+        let parts: Vec<String> = elevations.iter().enumerate()
+            .map(|(q, elev)| format!(r#"{{"q":{},"r":0,"elev":{}}}"#, q, elev))
+            .collect();
+        format!("[{}]", parts.join(","))
+    }
+    
+    /// Elevation map JSON from ((q, r), elev) entries
+    fn elevation_json(entries: &[((i32, i32), i32)]) -> String {
+        let parts: Vec<String> = entries.iter()
+            .map(|((q, r), elev)| format!(r#"{{"q":{},"r":{},"elev":{}}}"#, q, r, elev))
+            .collect();
+        format!("[{}]", parts.join(","))
+    }
+    
+    #[test]
+    fn river_stops_at_given_water() {
+        let river = generate_river(0, 0, row_json(&[9, 7, 5, 3, 1]), r#"[{"q":2,"r":0}]"#.to_string());
+        assert_eq!(river, r#"[{"q":0,"r":0},{"q":1,"r":0},{"q":2,"r":0}]"#);
+    }
+    
+    #[test]
+    fn river_stops_at_local_minimum() {
+        let river = generate_river(0, 0, row_json(&[9, 4, 6, 1]), "[]".to_string());
+        assert_eq!(river, r#"[{"q":0,"r":0},{"q":1,"r":0}]"#);
+        assert_eq!(generate_river(5, 5, row_json(&[9, 4]), "[]".to_string()), "[]");
+    }
+    
+    #[test]
+    fn slope_drains_to_lowest_edge_and_pit_to_itself() {
+        // Elevation rises with q, so everything drains toward the q = 0 edge
+        let slope: Vec<((i32, i32), i32)> = (0..5).flat_map(|q| (0..3).map(move |r| ((q, r), 10 * q))).collect();
+        let flows: Vec<serde_json::Value> = serde_json::from_str(&compute_flow_directions(elevation_json(&slope))).unwrap();
+        assert_eq!(flows.len(), slope.len());
+        for flow in &flows {
+            let (q, r) = (flow["q"].as_i64().unwrap(), flow["r"].as_i64().unwrap());
+            let target = (flow["flows_to"]["q"].as_i64().unwrap(), flow["flows_to"]["r"].as_i64().unwrap());
+            if q == 0 {
+                assert_eq!(target, (q, r));
+            } else {
+                assert_eq!(target.0, q - 1, "({}, {}) flows to {:?}", q, r, target);
+            }
+        }
+        
+        // A pit lower than all its neighbors flows to itself and collects its ring
+        let mut bowl: Vec<((i32, i32), i32)> = get_hex_neighbors(0, 0).into_iter().map(|hex| (hex, 5)).collect();
+        bowl.push(((0, 0), 1));
+        let bowl_flows = compute_flow_directions(elevation_json(&bowl));
+        assert!(bowl_flows.contains(r#"{"q":0,"r":0,"flows_to":{"q":0,"r":0}}"#));
+        assert!(bowl_flows.contains(r#"{"q":1,"r":0,"flows_to":{"q":0,"r":0}}"#));
+    }
+    
+    #[test]
+    fn valley_floor_collects_the_most_flow() {
+        // Walls rise away from q = 0 and the floor descends toward r = 5
+        let valley: Vec<((i32, i32), i32)> = (-3..=3).flat_map(|q: i32| (0..6).map(move |r| ((q, r), 10 * q.abs() - r))).collect();
+        let threshold = 10;
+        let rivers = parse_int_fields_json(&trace_rivers(elevation_json(&valley), threshold), &["q", "r", "accumulation"]);
+        
+        let mouth = rivers.iter().max_by_key(|fields| fields[2]).unwrap();
+        assert_eq!((mouth[0], mouth[1]), (0, 5));
+        assert_eq!(mouth[2], valley.len() as i32);
+        assert!(rivers.iter().all(|fields| fields[2] > threshold));
+        assert!(rivers.iter().any(|fields| (fields[0], fields[1]) == (0, 4)));
+    }
+    
+    #[test]
+    fn step_edge_has_the_largest_slope() {
+        let step: Vec<((i32, i32), i32)> = (-3..=3).flat_map(|q| (0..4).map(move |r| ((q, r), if q < 0 { 0 } else { 50 }))).collect();
+        let slopes = parse_int_fields_json(&compute_slopes(elevation_json(&step)), &["q", "r", "slope"]);
+        assert_eq!(slopes.len(), step.len());
+        for fields in slopes {
+            let expected = if fields[0] == -1 || fields[0] == 0 { 50 } else { 0 };
+            assert_eq!(fields[2], expected, "{:?}", fields);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Field of view module
+
+use wasm_bindgen::prelude::*;
+use std::collections::HashSet;
+use crate::types::CubeCoord;
+use crate::hex_utils::{parse_valid_terrain_json, axial_to_cube, cube_add, cube_scale, CUBE_DIRECTIONS};
+
+/// Shadow-casting state shared by the recursive sextant scans
+struct ShadowCaster<'a> {
+    origin: CubeCoord,
+    radius: i32,
+    blocking: &'a HashSet<(i32, i32)>,
+    visible: HashSet<(i32, i32)>,
+}
+
+impl ShadowCaster<'_> {
+    /// Cast light through one sextant, one ring (depth) at a time
+    ///
+    /// **Learning Point**: Within a sextant, the ring at `depth` holds `depth + 1` tiles
+    /// walking from the corner in `CUBE_DIRECTIONS[sextant]` along `CUBE_DIRECTIONS[sextant + 2]`.
+    /// Tile `i` spans the slope interval `[(i - 0.5) / depth, (i + 0.5) / depth]` where the
+    /// sextant covers `[0, 1]`. The lit interval `[start_slope, end_slope]` is narrowed past
+    /// blockers and split into a recursive call whenever a blocker interrupts it, so only
+    /// tiles that are actually lit are ever visited.
+    fn cast_light(&mut self, sextant: usize, depth: i32, start_slope: f64, end_slope: f64) {
+        if depth > self.radius || start_slope >= end_slope {
+            return;
+        }
+
+        let corner = cube_add(self.origin, cube_scale(CUBE_DIRECTIONS[sextant], depth));
+        let step = CUBE_DIRECTIONS[(sextant + 2) % 6];
+        let depth_f = depth as f64;
+
+        let mut start_slope = start_slope;
+        let mut prev_blocked: Option<bool> = None;
+
+        for i in 0..=depth {
+            let low = (i as f64 - 0.5) / depth_f;
+            let high = (i as f64 + 0.5) / depth_f;
+            let center = i as f64 / depth_f;
+
+            // Skip tiles left of the lit interval, stop once past it
+            if high <= start_slope {
+                continue;
+            }
+            if low >= end_slope {
+                break;
+            }
+
+            let tile = cube_add(corner, cube_scale(step, i));
+            let key = (tile.q, tile.r);
+
+            // A tile is visible when its center lies inside the lit interval
+            if center >= start_slope && center <= end_slope {
+                self.visible.insert(key);
+            }
+
+            if self.blocking.contains(&key) {
+                if prev_blocked == Some(false) {
+                    // Lit run ended at this blocker - continue it one ring further out
+                    self.cast_light(sextant, depth + 1, start_slope, low);
+                }
+                prev_blocked = Some(true);
+            } else {
+                if prev_blocked == Some(true) {
+                    // Light resumes right after the blocker
+                    start_slope = low;
+                }
+                prev_blocked = Some(false);
+            }
+        }
+
+        // Last run was lit - propagate it to the next ring
+        if prev_blocked == Some(false) {
+            self.cast_light(sextant, depth + 1, start_slope, end_slope);
+        }
+    }
+}
+
+/// Compute field of view from an origin using recursive hex shadow-casting
+///
+/// **Learning Point**: Sweeps the six sextants around the origin ring by ring and
+/// propagates lit slope intervals past blockers. Work is proportional to the number
+/// of visible tiles rather than tiles × line length, unlike repeated line-of-sight checks.
+/// Blocking tiles are themselves visible (you can see the wall) but hide what is behind them.
+/// The origin is always visible.
+///
+/// @param origin_q - Origin q coordinate (axial)
+/// @param origin_r - Origin r coordinate (axial)
+/// @param radius - Maximum view distance in hex steps
+/// @param blocking_json - JSON array of blocking hexes: [{"q":0,"r":0},...]
+/// @returns JSON array of visible hexes sorted by (q, r): [{"q":0,"r":0},...], or "[]" if radius is negative
+#[wasm_bindgen]
+pub fn compute_fov(origin_q: i32, origin_r: i32, radius: i32, blocking_json: String) -> String {
+    if radius < 0 {
+        return "[]".to_string();
+    }
+
+    let blocking = parse_valid_terrain_json(&blocking_json);
+    let origin = axial_to_cube(origin_q, origin_r);
+
+    let mut caster = ShadowCaster {
+        origin,
+        radius,
+        blocking: &blocking,
+        visible: HashSet::new(),
+    };
+    caster.visible.insert((origin_q, origin_r));
+
+    for sextant in 0..6 {
+        caster.cast_light(sextant, 1, 0.0, 1.0);
+    }
+
+    let mut visible_vec: Vec<(i32, i32)> = caster.visible.into_iter().collect();
+    visible_vec.sort();
+
+    let mut json_parts = Vec::new();
+    for (q, r) in visible_vec {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+
+    format!("[{}]", json_parts.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wall_hides_tiles_directly_behind_it() {
+        let visible = parse_valid_terrain_json(&compute_fov(0, 0, 4, r#"[{"q":1,"r":0}]"#.to_string()));
+        assert!(visible.contains(&(1, 0)));
+        assert!(!visible.contains(&(2, 0)) && !visible.contains(&(3, 0)) && !visible.contains(&(4, 0)));
+        assert!(visible.contains(&(0, 3)) && visible.contains(&(-3, 0)));
+    }
+}
@@ -1,7 +1,8 @@
 /// Hex coordinate utilities module
 
-use std::collections::HashSet;
-use crate::types::{HexCoord, CubeCoord};
+use std::collections::{HashMap, HashSet, VecDeque};
+use serde_json::Value;
+use crate::types::{HexCoord, CubeCoord, GridShape, HexOrientation, OffsetType, TileType};
 
 /// Cube directions for hex grid navigation
 pub const CUBE_DIRECTIONS: [CubeCoord; 6] = [
@@ -17,22 +18,25 @@ pub const CUBE_DIRECTIONS: [CubeCoord; 6] = [
 /// Uses axial coordinates converted to cube coordinates
 /// Formula: (|dq| + |dr| + |ds|) / 2 where s = -q - r
 /// This matches the Python example: (abs(q1-q2) + abs(r1-r2) + abs(s1-s2)) // 2
+/// Computed in i64 so any pair of i32 coordinates works; saturates at i32::MAX
 pub fn hex_distance(q1: i32, r1: i32, q2: i32, r2: i32) -> i32 {
-    let s1 = -q1 - r1;
-    let s2 = -q2 - r2;
-    ((q1 - q2).abs() + (r1 - r2).abs() + (s1 - s2).abs()) / 2
+    let dq = q1 as i64 - q2 as i64;
+    let dr = r1 as i64 - r2 as i64;
+    let ds = -dq - dr;
+    saturate_distance((dq.abs() + dr.abs() + ds.abs()) / 2)
+}
+
+/// Clamp a distance computed in i64 back into i32
+fn saturate_distance(distance: i64) -> i32 {
+    i32::try_from(distance).unwrap_or(i32::MAX)
 }
 
 /// Get all 6 hex neighbors of a coordinate (axial)
+/// Listed in `CUBE_DIRECTIONS` order, so neighbor `i` is direction `i`
 pub fn get_hex_neighbors(q: i32, r: i32) -> Vec<(i32, i32)> {
-    vec![
-        (q + 1, r),
-        (q - 1, r),
-        (q, r + 1),
-        (q, r - 1),
-        (q + 1, r - 1),
-        (q - 1, r + 1),
-    ]
+    CUBE_DIRECTIONS.iter()
+        .map(|direction| (q + direction.q, r + direction.r))
+        .collect()
 }
 
 /// Convert axial coordinates to cube coordinates
@@ -48,8 +52,12 @@ pub fn axial_to_cube(q: i32, r: i32) -> CubeCoord {
 /// Calculate cube distance between two cube coordinates
 /// Formula: max(|dq|, |dr|, |ds|)
 /// This matches TypeScript HEX_UTILS.cubeDistance
+/// Computed in i64 from q and r (ds = -dq - dr) so it never overflows; saturates at i32::MAX
 pub fn cube_distance(a: CubeCoord, b: CubeCoord) -> i32 {
-    (a.q - b.q).abs().max((a.r - b.r).abs()).max((a.s - b.s).abs())
+    let dq = a.q as i64 - b.q as i64;
+    let dr = a.r as i64 - b.r as i64;
+    let ds = -dq - dr;
+    saturate_distance(dq.abs().max(dr.abs()).max(ds.abs()))
 }
 
 /// Add two cube coordinates
@@ -70,6 +78,21 @@ pub fn cube_scale(hex: CubeCoord, factor: i32) -> CubeCoord {
     }
 }
 
+/// Rotate a cube coordinate about the origin by `steps` sixths of a turn
+/// One step maps (q, r, s) to (-r, -s, -q), e.g. direction 0 onto direction 5;
+/// negative steps rotate the other way
+pub fn cube_rotate(hex: CubeCoord, steps: i32) -> CubeCoord {
+    let mut rotated = hex;
+    for _ in 0..steps.rem_euclid(6) {
+        rotated = CubeCoord {
+            q: -rotated.r,
+            r: -rotated.s,
+            s: -rotated.q,
+        };
+    }
+    rotated
+}
+
 /// Get cube neighbor in specified direction (0-5)
 pub fn cube_neighbor(cube: CubeCoord, direction: usize) -> CubeCoord {
     cube_add(cube, CUBE_DIRECTIONS[direction % 6])
@@ -99,6 +122,118 @@ pub fn cube_ring(center: CubeCoord, radius: i32) -> Vec<CubeCoord> {
     results
 }
 
+/// Generate all tiles from the center outward in spiral order
+/// Center first, then each ring from radius 1 to max_radius in `cube_ring` order
+pub fn hex_spiral(center: CubeCoord, max_radius: i32) -> Vec<CubeCoord> {
+    let mut results = Vec::new();
+    for radius in 0..=max_radius {
+        results.extend(cube_ring(center, radius));
+    }
+    results
+}
+
+/// Convert axial coordinates to offset (col, row) coordinates
+/// `r & 1` / `q & 1` is 1 for odd values, negative ones included, so the halving is exact
+pub fn axial_to_offset(q: i32, r: i32, offset_type: OffsetType) -> (i32, i32) {
+    match offset_type {
+        OffsetType::OddR => (q + (r - (r & 1)) / 2, r),
+        OffsetType::EvenR => (q + (r + (r & 1)) / 2, r),
+        OffsetType::OddQ => (q, r + (q - (q & 1)) / 2),
+        OffsetType::EvenQ => (q, r + (q + (q & 1)) / 2),
+    }
+}
+
+/// Convert axial coordinates to the pixel position of the hex center
+/// Screen convention: x grows right, y grows down
+/// - Pointy-top: x = size * √3 * (q + r/2), y = size * 3/2 * r
+/// - Flat-top:   x = size * 3/2 * q,        y = size * √3 * (r + q/2)
+pub fn hex_center_pixel(q: i32, r: i32, size: f64, orientation: HexOrientation) -> (f64, f64) {
+    let sqrt3 = 3.0_f64.sqrt();
+    let q_f = q as f64;
+    let r_f = r as f64;
+    match orientation {
+        HexOrientation::PointyTop => (size * sqrt3 * (q_f + r_f / 2.0), size * 1.5 * r_f),
+        HexOrientation::FlatTop => (size * 1.5 * q_f, size * sqrt3 * (r_f + q_f / 2.0)),
+    }
+}
+
+/// Round fractional axial coordinates to the nearest hex
+/// Rounds all three cube components, then recomputes the one with the largest
+/// rounding error from the other two so that q + r + s stays 0
+pub fn cube_round(q: f64, r: f64) -> (i32, i32) {
+    let s = -q - r;
+    let mut rq = q.round();
+    let mut rr = r.round();
+    let rs = s.round();
+    
+    let dq = (rq - q).abs();
+    let dr = (rr - r).abs();
+    let ds = (rs - s).abs();
+    
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    
+    (rq as i32, rr as i32)
+}
+
+/// Convert a pixel position to the axial coordinates of the hex containing it
+/// Inverse of `hex_center_pixel` (same screen convention), followed by `cube_round`
+pub fn pixel_to_axial(x: f64, y: f64, size: f64, orientation: HexOrientation) -> (i32, i32) {
+    let sqrt3 = 3.0_f64.sqrt();
+    let (q, r) = match orientation {
+        HexOrientation::PointyTop => ((sqrt3 / 3.0 * x - y / 3.0) / size, (2.0 / 3.0 * y) / size),
+        HexOrientation::FlatTop => ((2.0 / 3.0 * x) / size, (-x / 3.0 + sqrt3 / 3.0 * y) / size),
+    };
+    cube_round(q, r)
+}
+
+/// Draw a straight line of hexes from start to end, both inclusive
+/// Samples `hex_distance + 1` evenly spaced points along the straight line in cube
+/// space and rounds each with `cube_round`. Both endpoints are nudged by a tiny epsilon
+/// so points that land exactly on an edge between two hexes always round the same way
+pub fn hex_line(q1: i32, r1: i32, q2: i32, r2: i32) -> Vec<(i32, i32)> {
+    let steps = hex_distance(q1, r1, q2, r2);
+    if steps == 0 {
+        return vec![(q1, r1)];
+    }
+    
+    let (start_q, start_r) = (q1 as f64 + 1e-6, r1 as f64 + 1e-6);
+    let (end_q, end_r) = (q2 as f64 + 1e-6, r2 as f64 + 1e-6);
+    
+    (0..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            cube_round(start_q + (end_q - start_q) * t, start_r + (end_r - start_r) * t)
+        })
+        .collect()
+}
+
+/// Get the 6 corner pixel positions of a hex
+/// Corner i sits at angle 60*i - 30 degrees (pointy-top) or 60*i degrees (flat-top),
+/// so for either orientation the edge facing `CUBE_DIRECTIONS[d]` runs from corner
+/// `(6 - d) % 6` to corner `(7 - d) % 6`
+pub fn hex_corners(q: i32, r: i32, size: f64, orientation: HexOrientation) -> [(f64, f64); 6] {
+    let (cx, cy) = hex_center_pixel(q, r, size, orientation);
+    let half_sqrt3 = 3.0_f64.sqrt() / 2.0;
+    
+    // Unit corner offsets as exact constants (avoids cos/sin noise like 6e-17 for 0)
+    let unit: [(f64, f64); 6] = match orientation {
+        HexOrientation::PointyTop => [
+            (half_sqrt3, -0.5), (half_sqrt3, 0.5), (0.0, 1.0),
+            (-half_sqrt3, 0.5), (-half_sqrt3, -0.5), (0.0, -1.0),
+        ],
+        HexOrientation::FlatTop => [
+            (1.0, 0.0), (0.5, half_sqrt3), (-0.5, half_sqrt3),
+            (-1.0, 0.0), (-0.5, -half_sqrt3), (0.5, -half_sqrt3),
+        ],
+    };
+    
+    unit.map(|(ux, uy)| (cx + size * ux, cy + size * uy))
+}
+
 /// Generate hexagon grid up to max_layer
 /// Returns all hex coordinates within the hexagon pattern
 /// Matches TypeScript implementation using cube coordinates
@@ -131,148 +266,213 @@ pub fn generate_hex_grid(max_layer: i32, center_q: i32, center_r: i32) -> Vec<He
     grid
 }
 
-/// Parse valid terrain JSON string into HashSet
-/// Format: [{"q":0,"r":0},{"q":1,"r":0},...]
-/// Returns empty HashSet if parsing fails
-pub fn parse_valid_terrain_json(valid_terrain_json: &str) -> HashSet<(i32, i32)> {
-    let mut valid_terrain = HashSet::new();
-    
-    let trimmed = valid_terrain_json.trim();
-    if trimmed.is_empty() || trimmed == "[]" {
-        return valid_terrain;
+/// Generate a chunk grid of the given shape around a center
+/// Bounds for layer L = max_layer, relative to the center (dq = q - center_q, dr = r - center_r):
+/// - Hexagon: hex distance <= L (exactly `generate_hex_grid`)
+/// - Rectangle: -L <= dr <= L and -L <= dq + floor(dr / 2) <= L, i.e. 2L+1 rows of 2L+1
+///   hexes, each row shifted to stay vertically aligned for pointy-top hexes
+/// - Parallelogram: -L <= dq <= L and -L <= dr <= L (axial rhombus); repeats without
+///   overlap when centers step by 2L+1 in q or r
+/// 
+/// A negative max_layer gives an empty grid for every shape
+pub fn generate_grid(shape: GridShape, max_layer: i32, center_q: i32, center_r: i32) -> Vec<HexCoord> {
+    let mut grid = Vec::new();
+    match shape {
+        GridShape::Hexagon => return generate_hex_grid(max_layer, center_q, center_r),
+        GridShape::Rectangle => {
+            for dr in -max_layer..=max_layer {
+                let shift = dr.div_euclid(2);
+                for dq in (-max_layer - shift)..=(max_layer - shift) {
+                    grid.push(HexCoord { q: center_q + dq, r: center_r + dr });
+                }
+            }
+        }
+        GridShape::Parallelogram => {
+            for dr in -max_layer..=max_layer {
+                for dq in -max_layer..=max_layer {
+                    grid.push(HexCoord { q: center_q + dq, r: center_r + dr });
+                }
+            }
+        }
     }
+    grid
+}
+
+/// Split a set of hexes into connected components (BFS over hex neighbors)
+/// Components are ordered by their smallest (q, r) member and their members are sorted
+pub fn connected_components(hexes: &HashSet<(i32, i32)>) -> Vec<Vec<(i32, i32)>> {
+    let mut sorted: Vec<(i32, i32)> = hexes.iter().copied().collect();
+    sorted.sort();
+    
+    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    let mut components = Vec::new();
     
-    // Simple JSON parsing: find all {"q":X,"r":Y} patterns
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        // Look for opening brace
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                // Look for "q" or "r" followed by colon and number
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    // Skip colon and whitespace
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    // Parse number
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    // Skip colon and whitespace
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    // Parse number
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
+    for start in sorted {
+        if !visited.insert(start) {
+            continue;
+        }
+        
+        let mut component = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some((q, r)) = queue.pop_front() {
+            component.push((q, r));
+            for neighbor in get_hex_neighbors(q, r) {
+                if hexes.contains(&neighbor) && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
                 }
             }
-            
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                valid_terrain.insert((q, r));
-            }
         }
-        i += 1;
+        
+        component.sort();
+        components.push(component);
     }
     
-    valid_terrain
+    components
+}
+
+/// Parse coordinate objects from a JSON array
+/// Format: [{"q":0,"r":0},{"q":1,"r":0},...] (extra fields are ignored)
+/// Every coordinate parser goes through here. Whitespace and newlines are accepted
+/// between tokens, and objects missing q or r, or with a non-integer q or r, are
+/// skipped without failing the rest. Input order is preserved.
+pub fn parse_coords(json: &str) -> Vec<(i32, i32)> {
+    parse_int_fields_json(json, &["q", "r"])
+        .into_iter()
+        .map(|fields| (fields[0], fields[1]))
+        .collect()
+}
+
+/// Parse valid terrain JSON string into HashSet
+/// Format: [{"q":0,"r":0},{"q":1,"r":0},...]
+/// Returns empty HashSet if parsing fails
+pub fn parse_valid_terrain_json(valid_terrain_json: &str) -> HashSet<(i32, i32)> {
+    parse_coords(valid_terrain_json).into_iter().collect()
 }
 
 /// Parse path JSON and return vector of coordinates
 /// Format: [{"q":0,"r":0},{"q":1,"r":0},...]
 pub fn parse_path_json(path_json: &str) -> Vec<(i32, i32)> {
-    let mut path = Vec::new();
+    parse_coords(path_json)
+}
+
+/// Parse integer fields from a JSON array of flat objects
+/// Format: [{"q":0,"r":0,"cost":3},...] (a single object is read as a one-element array)
+/// Returns one Vec per object holding the values in the same order as `keys`.
+/// Parsed with serde_json, so any whitespace is accepted and malformed or truncated
+/// input yields an empty Vec instead of panicking. Booleans read as 1 (true) and
+/// 0 (false). Objects missing any of the requested keys, or whose value is not an
+/// integer in i32 range or a boolean (e.g. a string or a float), are skipped.
+pub fn parse_int_fields_json(json: &str, keys: &[&str]) -> Vec<Vec<i32>> {
+    let objects = match serde_json::from_str::<Value>(json) {
+        Ok(Value::Array(items)) => items,
+        Ok(object @ Value::Object(_)) => vec![object],
+        _ => return Vec::new(),
+    };
+    
+    objects
+        .iter()
+        .filter_map(|object| {
+            keys.iter()
+                .map(|key| match object.get(key)? {
+                    Value::Bool(flag) => Some(*flag as i32),
+                    value => value.as_i64().and_then(|n| i32::try_from(n).ok()),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Parse per-tile movement costs into a HashMap
+/// Format: [{"q":0,"r":0,"cost":3},...]
+/// Entries with a cost below 1 are dropped, so those tiles are impassable
+pub fn parse_cost_json(cost_json: &str) -> HashMap<(i32, i32), i32> {
+    let mut costs = HashMap::new();
     
-    if path_json == "null" || path_json.is_empty() {
-        return path;
+    for fields in parse_int_fields_json(cost_json, &["q", "r", "cost"]) {
+        if fields[2] >= 1 {
+            costs.insert((fields[0], fields[1]), fields[2]);
+        }
     }
     
-    let trimmed = path_json.trim();
-    if trimmed == "[]" || trimmed.len() < 3 {
-        return path;
+    costs
+}
+
+/// Parse per-tile elevations into a HashMap
+/// Format: [{"q":0,"r":0,"elev":12},...]
+/// Elevations are integers; a later duplicate of a tile overrides an earlier one
+pub fn parse_elevation_json(elevation_json: &str) -> HashMap<(i32, i32), i32> {
+    parse_int_fields_json(elevation_json, &["q", "r", "elev"])
+        .into_iter()
+        .map(|fields| ((fields[0], fields[1]), fields[2]))
+        .collect()
+}
+
+/// Parse a tile map into a vector of coordinates and tile types
+/// Format: [{"q":0,"r":0,"tileType":3},...]
+/// Entries with an invalid tile type are skipped; input order is preserved
+pub fn parse_tile_map_json(tiles_json: &str) -> Vec<((i32, i32), TileType)> {
+    parse_int_fields_json(tiles_json, &["q", "r", "tileType"])
+        .into_iter()
+        .filter_map(|fields| TileType::from_i32(fields[2]).map(|tile_type| ((fields[0], fields[1]), tile_type)))
+        .collect()
+}
+
+/// Serialize a tile map as JSON sorted by (q, r)
+/// Format: [{"q":0,"r":0,"tileType":3},...]
+pub fn tile_map_to_json(tiles: &HashMap<(i32, i32), TileType>) -> String {
+    let mut tile_vec: Vec<((i32, i32), TileType)> = tiles.iter().map(|(k, v)| (*k, *v)).collect();
+    tile_vec.sort_by_key(|(key, _)| *key);
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in tile_vec {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, tile_type as i32));
     }
     
-    // Simple JSON parsing: find all {"q":X,"r":Y} patterns
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
-            }
-            
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                path.push((q, r));
-            }
+    format!("[{}]", json_parts.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parse_coords_accepts_whitespace() {
+        let json = "[ {\n \"q\" : 1 ,\t\"r\": -2 } ,\n{\"r\":3,\"q\":0} ]";
+        assert_eq!(parse_coords(json), vec![(1, -2), (0, 3)]);
+    }
+    
+    #[test]
+    fn parse_coords_keeps_final_element() {
+        assert_eq!(parse_coords(r#"[{"q":0,"r":0},{"q":5,"r":6}]"#), vec![(0, 0), (5, 6)]);
+        assert_eq!(parse_coords(r#"[{"q":7,"r":8}]"#), vec![(7, 8)]);
+    }
+    
+    #[test]
+    fn parse_coords_rejects_truncated_input() {
+        for json in ["[{\"q", "[{\"q\":1,\"r\":", "[{\"q\":1,\"r\":2}", "[", "{", ""] {
+            assert!(parse_coords(json).is_empty(), "{:?}", json);
         }
-        i += 1;
     }
     
-    path
+    #[test]
+    fn parse_int_fields_skips_string_values() {
+        let json = r#"[{"q":"1","r":2},{"q":3,"r":4},{"q":1.5,"r":0},{"q":null,"r":0}]"#;
+        assert_eq!(parse_coords(json), vec![(3, 4)]);
+        assert_eq!(parse_int_fields_json(r#"[{"q":0,"r":0,"enabled":true}]"#, &["q", "r", "enabled"]), vec![vec![0, 0, 1]]);
+    }
+    
+    #[test]
+    fn distance_at_extreme_coordinates_saturates_instead_of_overflowing() {
+        assert_eq!(hex_distance(i32::MAX, 0, i32::MIN, 0), i32::MAX);
+        assert_eq!(hex_distance(i32::MIN, i32::MIN, i32::MAX, i32::MAX), i32::MAX);
+        // Differences overflow i32 but the distance itself still fits
+        assert_eq!(hex_distance(1_000_000_000, 0, -1_000_000_000, 0), 2_000_000_000);
+        assert_eq!(hex_distance(i32::MAX, 0, i32::MAX - 3, 0), 3);
+        
+        let a = CubeCoord { q: i32::MAX, r: 0, s: -i32::MAX };
+        let b = CubeCoord { q: i32::MIN, r: i32::MAX, s: 1 };
+        assert_eq!(cube_distance(a, b), i32::MAX);
+        assert_eq!(cube_distance(b, a), i32::MAX);
+        assert_eq!(cube_distance(axial_to_cube(1_000_000_000, 0), axial_to_cube(-1_000_000_000, 0)), 2_000_000_000);
+    }
 }
-
@@ -1,8 +1,10 @@
 /// WFC layout generation module
 
 use wasm_bindgen::prelude::*;
-use crate::state::WFC_STATE;
-use crate::types::TileType;
+use std::collections::{HashMap, HashSet};
+use crate::state::{WFC_STATE, lock_or_recover};
+use crate::types::{OffsetType, TileType};
+use crate::hex_utils::{axial_to_cube, axial_to_offset, connected_components, cube_rotate, cube_round, get_hex_neighbors, hex_distance, parse_int_fields_json, parse_tile_map_json, tile_map_to_json};
 
 /// Initialize the WASM module
 #[wasm_bindgen(start)]
@@ -26,7 +28,7 @@ pub fn get_wasm_version() -> String {
 /// 2. Fill any remaining empty cells with grass (shouldn't happen if pre-constraints are complete)
 #[wasm_bindgen]
 pub fn generate_layout() {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     state.clear();
     
     // Step 1: Apply pre-constraints to grid
@@ -51,7 +53,7 @@ pub fn generate_layout() {
 /// @returns Tile type as i32, or -1 if invalid/empty
 #[wasm_bindgen]
 pub fn get_tile_at(q: i32, r: i32) -> i32 {
-    let state = WFC_STATE.lock().unwrap();
+    let state = lock_or_recover(&WFC_STATE);
     if let Some(tile) = state.get_tile(q, r) {
         tile as i32
     } else {
@@ -65,7 +67,7 @@ pub fn get_tile_at(q: i32, r: i32) -> i32 {
 /// the user clicks "Recompute Wave Collapse" to start fresh.
 #[wasm_bindgen]
 pub fn clear_layout() {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     state.clear();
 }
 
@@ -76,20 +78,16 @@ pub fn clear_layout() {
 /// 
 /// @param q - Hex column coordinate (axial q)
 /// @param r - Hex row coordinate (axial r)
-/// @param tile_type - Tile type as i32 (0-4, matching TileType enum)
+/// @param tile_type - Tile type as i32 (0-6, matching TileType enum)
 /// @returns true if constraint was set successfully, false if tile type is invalid
 #[wasm_bindgen]
 pub fn set_pre_constraint(q: i32, r: i32, tile_type: i32) -> bool {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     
     // Convert i32 to TileType
-    let tile = match tile_type {
-        0 => TileType::Grass,
-        1 => TileType::Building,
-        2 => TileType::Road,
-        3 => TileType::Forest,
-        4 => TileType::Water,
-        _ => return false, // Invalid tile type
+    let tile = match TileType::from_i32(tile_type) {
+        Some(tile) => tile,
+        None => return false, // Invalid tile type
     };
     
     state.set_pre_constraint(q, r, tile)
@@ -101,10 +99,67 @@ pub fn set_pre_constraint(q: i32, r: i32, tile_type: i32) -> bool {
 /// completely random layouts again. Useful for resetting after text-guided generation.
 #[wasm_bindgen]
 pub fn clear_pre_constraints() {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     state.clear_pre_constraints();
 }
 
+/// Set many pre-constraints in one call
+/// 
+/// **Learning Point**: Loading a painted template one cell at a time through
+/// `set_pre_constraint` costs one WASM boundary crossing per tile. This parses the whole
+/// batch in Rust instead. An entry with an invalid tile type is rejected on its own;
+/// the rest of the batch is still applied.
+/// 
+/// @param json - JSON array of constraints: [{"q":0,"r":0,"tileType":N},...] (N = 0-6)
+/// @returns Number of constraints accepted
+#[wasm_bindgen]
+pub fn set_pre_constraints_json(json: String) -> i32 {
+    let mut state = lock_or_recover(&WFC_STATE);
+    
+    let mut accepted = 0;
+    for fields in parse_int_fields_json(&json, &["q", "r", "tileType"]) {
+        if let Some(tile_type) = TileType::from_i32(fields[2]) {
+            if state.set_pre_constraint(fields[0], fields[1], tile_type) {
+                accepted += 1;
+            }
+        }
+    }
+    accepted
+}
+
+/// Get all current pre-constraints as JSON
+/// 
+/// **Learning Point**: Reads back what `set_pre_constraint` / `set_pre_constraints_json`
+/// have set, in the same format, sorted by (q, r).
+/// 
+/// @returns JSON array of constraints: [{"q":0,"r":0,"tileType":N},...]
+#[wasm_bindgen]
+pub fn get_pre_constraints_json() -> String {
+    let state = lock_or_recover(&WFC_STATE);
+    let constraints: HashMap<(i32, i32), TileType> = state.pre_constraints().collect();
+    tile_map_to_json(&constraints)
+}
+
+/// Check that the painted road pre-constraints form one connected network
+/// 
+/// **Learning Point**: Works on the pre-constraint layer rather than on JSON input
+/// (unlike `validate_road_connectivity`), so painted roads can be verified before
+/// calling `generate_layout`. All `Road` pre-constraints are split into connected
+/// components; the network is connected when there is at most one component.
+/// 
+/// @returns true if road pre-constraints are empty, a single tile, or one connected component
+#[wasm_bindgen]
+pub fn validate_constraint_roads_connected() -> bool {
+    let state = lock_or_recover(&WFC_STATE);
+    
+    let roads: HashSet<(i32, i32)> = state.pre_constraints()
+        .filter(|(_, tile_type)| *tile_type == TileType::Road)
+        .map(|(key, _)| key)
+        .collect();
+    
+    connected_components(&roads).len() <= 1
+}
+
 /// Get statistics about the current grid
 /// 
 /// **Learning Point**: This function iterates over the hash map to count all tile types.
@@ -115,13 +170,15 @@ pub fn clear_pre_constraints() {
 /// @returns JSON string with tile counts: {"grass":X,"building":Y,"road":Z,"forest":A,"water":B,"total":C}
 #[wasm_bindgen]
 pub fn get_stats() -> String {
-    let state = WFC_STATE.lock().unwrap();
+    let state = lock_or_recover(&WFC_STATE);
     
     let mut grass = 0;
     let mut building = 0;
     let mut road = 0;
     let mut forest = 0;
     let mut water = 0;
+    let mut mountain = 0;
+    let mut sand = 0;
     
     for tile_type in state.grid_values() {
         match tile_type {
@@ -130,14 +187,485 @@ pub fn get_stats() -> String {
             TileType::Road => road += 1,
             TileType::Forest => forest += 1,
             TileType::Water => water += 1,
+            TileType::Mountain => mountain += 1,
+            TileType::Sand => sand += 1,
+        }
+    }
+    
+    let total = grass + building + road + forest + water + mountain + sand;
+    
+    format!(
+        r#"{{"grass":{},"building":{},"road":{},"forest":{},"water":{},"mountain":{},"sand":{},"total":{}}}"#,
+        grass, building, road, forest, water, mountain, sand, total
+    )
+}
+
+/// Get the positions of every grid tile of one type
+/// 
+/// **Learning Point**: The companion to `get_stats`: instead of counting each type it
+/// returns where the tiles are, in one boundary crossing instead of a `get_tile_at`
+/// call per cell. The road list can go straight into `validate_road_connectivity`.
+/// Output is sorted by (q, r).
+/// 
+/// @param tile_type - Tile type to collect (0-6)
+/// @returns JSON array of coordinates: [{"q":0,"r":0},...], or "null" if tile_type is invalid
+#[wasm_bindgen]
+pub fn get_tiles_of_type(tile_type: i32) -> String {
+    let tile_type = match TileType::from_i32(tile_type) {
+        Some(tile_type) => tile_type,
+        None => return "null".to_string(),
+    };
+    
+    let state = lock_or_recover(&WFC_STATE);
+    let mut coords: Vec<(i32, i32)> = state.grid_tiles()
+        .filter(|(_, tile)| *tile == tile_type)
+        .map(|(key, _)| key)
+        .collect();
+    coords.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in coords {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Find the grid tile of one type closest to a hex
+/// 
+/// **Learning Point**: Scans the grid once and keeps the matching tile with the smallest
+/// hex distance, e.g. the water tile nearest a building, without exporting the grid to
+/// JS. Ties go to the smallest (q, r), so the answer never depends on hash map order.
+/// 
+/// @param q - Hex q coordinate to search from
+/// @param r - Hex r coordinate to search from
+/// @param tile_type - Tile type to look for (0-6)
+/// @returns JSON object {"q":0,"r":0,"distance":3}, or "null" if tile_type is invalid or no tile of that type exists
+#[wasm_bindgen]
+pub fn nearest_tile_of_type(q: i32, r: i32, tile_type: i32) -> String {
+    let tile_type = match TileType::from_i32(tile_type) {
+        Some(tile_type) => tile_type,
+        None => return "null".to_string(),
+    };
+    
+    let state = lock_or_recover(&WFC_STATE);
+    let nearest = state.grid_tiles()
+        .filter(|(_, tile)| *tile == tile_type)
+        .map(|((tile_q, tile_r), _)| (hex_distance(q, r, tile_q, tile_r), tile_q, tile_r))
+        .min();
+    
+    match nearest {
+        Some((distance, tile_q, tile_r)) => format!(
+            r#"{{"q":{},"r":{},"distance":{}}}"#,
+            tile_q, tile_r, distance
+        ),
+        None => "null".to_string(),
+    }
+}
+
+/// Get every grid tile within a hex distance of a point
+/// 
+/// **Learning Point**: Area-effect query over the generated grid: one pass keeps the
+/// tiles whose `hex_distance` to (q, r) is at most `range`, so JS gets just the affected
+/// tiles instead of the whole grid. Range 0 returns the center alone (if it is on the
+/// grid); a negative range returns an empty array. Output is sorted by (q, r).
+/// 
+/// @param q - Center q coordinate
+/// @param r - Center r coordinate
+/// @param range - Maximum hex distance, inclusive
+/// @returns JSON array of coordinates: [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn tiles_within_range(q: i32, r: i32, range: i32) -> String {
+    let state = lock_or_recover(&WFC_STATE);
+    let mut coords: Vec<(i32, i32)> = state.grid_tiles()
+        .map(|(key, _)| key)
+        .filter(|&(tile_q, tile_r)| hex_distance(q, r, tile_q, tile_r) <= range)
+        .collect();
+    coords.sort();
+    
+    let mut json_parts = Vec::new();
+    for (tile_q, tile_r) in coords {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, tile_q, tile_r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Make the generated grid rotationally symmetric
+/// 
+/// **Learning Point**: Competitive maps need every player to start with the same
+/// terrain. One sector of the grid is the source and its tiles are copied onto the
+/// rotated positions in the other sectors, using `cube_rotate` on offsets from the grid
+/// center (the rounded mean of all grid coordinates, which is the chunk center for a
+/// hexagonal chunk):
+/// - 2-fold (180°): the source is the half with (dq, dr) > (0, 0) in (q, r) order
+/// - 6-fold (60°): the source is the wedge with dq > 0 and dr >= 0
+/// 
+/// The center tile maps onto itself. Tiles whose source position is not on the grid
+/// (possible for non-hexagonal grids) are left unchanged. Every tile reads from a
+/// snapshot, so the result does not depend on iteration order.
+/// 
+/// Call after `generate_layout`; asymmetric tiles are overwritten in place.
+/// 
+/// @param symmetry - Rotational order: 2 or 6
+/// @returns Number of tiles that changed type, or -1 if symmetry is not 2 or 6
+#[wasm_bindgen]
+pub fn apply_symmetry(symmetry: i32) -> i32 {
+    let (step, in_source): (i32, fn(i32, i32) -> bool) = match symmetry {
+        2 => (3, |dq, dr| (dq, dr) > (0, 0)),
+        6 => (1, |dq, dr| dq > 0 && dr >= 0),
+        _ => return -1,
+    };
+    
+    let mut state = lock_or_recover(&WFC_STATE);
+    let snapshot: HashMap<(i32, i32), TileType> = state.grid_tiles().collect();
+    if snapshot.is_empty() {
+        return 0;
+    }
+    
+    let count = snapshot.len() as f64;
+    let mean_q = snapshot.keys().map(|&(q, _)| q as f64).sum::<f64>() / count;
+    let mean_r = snapshot.keys().map(|&(_, r)| r as f64).sum::<f64>() / count;
+    let (center_q, center_r) = cube_round(mean_q, mean_r);
+    
+    let mut changed = 0;
+    for (&(q, r), &tile_type) in &snapshot {
+        let offset = axial_to_cube(q - center_q, r - center_r);
+        let source = (0..6 / step)
+            .map(|k| cube_rotate(offset, k * step))
+            .find(|rotated| in_source(rotated.q, rotated.r));
+        let source_type = match source {
+            Some(rotated) => snapshot.get(&(center_q + rotated.q, center_r + rotated.r)),
+            None => None,
+        };
+        if let Some(&source_type) = source_type {
+            if source_type != tile_type {
+                state.insert_tile(q, r, source_type);
+                changed += 1;
+            }
+        }
+    }
+    
+    changed
+}
+
+/// Smooth the generated grid with a cellular-automaton majority filter
+/// 
+/// **Learning Point**: Voronoi regions meet along hard, jagged edges. Each pass sets
+/// every tile to the most common type among its on-grid neighbors, which erodes
+/// single-tile specks and rounds region edges. A tie for the most common type keeps
+/// the tile's current type. Each pass reads from a snapshot of the previous pass, so a
+/// flip never cascades into its neighbors within the same pass.
+/// 
+/// @param iterations - Number of passes (0 or negative leaves the grid unchanged)
+/// @returns Total number of tile changes across all passes
+#[wasm_bindgen]
+pub fn smooth_tiles(iterations: i32) -> i32 {
+    let mut state = lock_or_recover(&WFC_STATE);
+    let mut changed = 0;
+    
+    for _ in 0..iterations.max(0) {
+        let snapshot: HashMap<(i32, i32), TileType> = state.grid_tiles().collect();
+        let mut pass_changed = 0;
+        
+        for (&(q, r), &tile_type) in &snapshot {
+            let mut counts = [0usize; TileType::COUNT];
+            for neighbor in get_hex_neighbors(q, r) {
+                if let Some(&neighbor_type) = snapshot.get(&neighbor) {
+                    counts[neighbor_type as usize] += 1;
+                }
+            }
+            
+            let best = counts.iter().copied().max().unwrap_or(0);
+            let mut leaders = (0..TileType::COUNT).filter(|&index| counts[index] == best);
+            let majority = match (leaders.next(), leaders.next()) {
+                (Some(index), None) if best > 0 => TileType::from_i32(index as i32),
+                _ => None,
+            };
+            
+            if let Some(majority) = majority {
+                if majority != tile_type {
+                    state.insert_tile(q, r, majority);
+                    pass_changed += 1;
+                }
+            }
+        }
+        
+        if pass_changed == 0 {
+            break;
         }
+        changed += pass_changed;
+    }
+    
+    changed
+}
+
+
+/// Export the whole grid as JSON for saving
+/// 
+/// **Learning Point**: Serializes every tile in one boundary crossing instead of one
+/// `get_tile_at` call per cell. The output is sorted by (q, r) so the same grid always
+/// produces the same string, and it is the format `import_layout_json` reads back.
+/// 
+/// @returns JSON array of tiles: [{"q":0,"r":0,"tileType":N},...]
+#[wasm_bindgen]
+pub fn export_layout_json() -> String {
+    let state = lock_or_recover(&WFC_STATE);
+    let tiles: HashMap<(i32, i32), TileType> = state.grid_tiles().collect();
+    tile_map_to_json(&tiles)
+}
+
+/// Replace the grid with a layout saved by `export_layout_json`
+/// 
+/// **Learning Point**: Clears the grid and repopulates it from the JSON. Entries with
+/// an invalid tile type are skipped. Pre-constraints are left untouched, so a restored
+/// layout can still be regenerated from the same constraints later.
+/// 
+/// @param json - JSON array of tiles: [{"q":0,"r":0,"tileType":N},...]
+#[wasm_bindgen]
+pub fn import_layout_json(json: String) {
+    let mut state = lock_or_recover(&WFC_STATE);
+    state.clear();
+    for ((q, r), tile_type) in parse_tile_map_json(&json) {
+        state.insert_tile(q, r, tile_type);
+    }
+}
+
+/// Export the current grid as a dense 2D offset-coordinate array for tilemap tools
+/// 
+/// **Learning Point**: Tilemap editors (Tiled, etc.) store maps as row-major 2D arrays.
+/// Each axial tile is converted with `axial_to_offset`, then placed into the smallest
+/// rectangle covering every tile. Cells with no tile (the corners a hexagon leaves
+/// empty, or holes) are -1. `originCol`/`originRow` give the offset coordinates of
+/// `rows[0][0]`, so cell `rows[y][x]` is offset coordinate `(originCol + x, originRow + y)`.
+/// 
+/// @param offset_type - 0 = odd-r, 1 = even-r, 2 = odd-q, 3 = even-q
+/// @returns JSON object: {"originCol":0,"originRow":0,"rows":[[0,-1,...],...]},
+///          or "null" if offset_type is invalid
+#[wasm_bindgen]
+pub fn export_grid_2d(offset_type: i32) -> String {
+    let offset_type = match OffsetType::from_i32(offset_type) {
+        Some(offset_type) => offset_type,
+        None => return "null".to_string(),
+    };
+    
+    let state = lock_or_recover(&WFC_STATE);
+    let cells: Vec<((i32, i32), TileType)> = state.grid_tiles()
+        .map(|((q, r), tile_type)| (axial_to_offset(q, r, offset_type), tile_type))
+        .collect();
+    
+    if cells.is_empty() {
+        return r#"{"originCol":0,"originRow":0,"rows":[]}"#.to_string();
+    }
+    
+    let min_col = cells.iter().map(|&((col, _), _)| col).min().unwrap_or(0);
+    let max_col = cells.iter().map(|&((col, _), _)| col).max().unwrap_or(0);
+    let min_row = cells.iter().map(|&((_, row), _)| row).min().unwrap_or(0);
+    let max_row = cells.iter().map(|&((_, row), _)| row).max().unwrap_or(0);
+    
+    let width = (max_col - min_col + 1) as usize;
+    let height = (max_row - min_row + 1) as usize;
+    let mut rows = vec![vec![-1; width]; height];
+    for ((col, row), tile_type) in cells {
+        rows[(row - min_row) as usize][(col - min_col) as usize] = tile_type as i32;
     }
     
-    let total = grass + building + road + forest + water;
+    let row_parts: Vec<String> = rows.iter()
+        .map(|row| {
+            let values: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+            format!("[{}]", values.join(","))
+        })
+        .collect();
     
     format!(
-        r#"{{"grass":{},"building":{},"road":{},"forest":{},"water":{},"total":{}}}"#,
-        grass, building, road, forest, water, total
+        r#"{{"originCol":{},"originRow":{},"rows":[{}]}}"#,
+        min_col, min_row, row_parts.join(",")
     )
 }
 
+/// Apply a batch of tile edits to the grid and return the edits that undo them
+/// 
+/// **Learning Point**: This is the core primitive for an undo stack. Each edit records
+/// the cell's previous value before overwriting it, and the inverse list is returned in
+/// reverse order - so feeding it back through `apply_edits` restores the grid exactly,
+/// even when the batch edits the same cell more than once. A tile type of -1 means
+/// "no tile": it removes the cell, and it is what the inverse uses for cells that were
+/// empty before. Edits with any other invalid tile type are skipped.
+/// 
+/// @param edits_json - JSON array of edits: [{"q":0,"r":0,"tileType":N},...] (N = 0-6, or -1 to remove)
+/// @returns JSON array of inverse edits: [{"q":0,"r":0,"tileType":OLD},...]
+#[wasm_bindgen]
+pub fn apply_edits(edits_json: String) -> String {
+    let mut state = lock_or_recover(&WFC_STATE);
+    
+    let mut inverse = Vec::new();
+    for fields in parse_int_fields_json(&edits_json, &["q", "r", "tileType"]) {
+        let (q, r, tile_type) = (fields[0], fields[1], fields[2]);
+        
+        let previous = if tile_type == -1 {
+            state.remove_tile(q, r)
+        } else if let Some(tile_type) = TileType::from_i32(tile_type) {
+            let previous = state.get_tile(q, r);
+            state.insert_tile(q, r, tile_type);
+            previous
+        } else {
+            continue;
+        };
+        
+        let old = previous.map(|tile_type| tile_type as i32).unwrap_or(-1);
+        inverse.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, old));
+    }
+    
+    inverse.reverse();
+    format!("[{}]", inverse.join(","))
+}
+
+/// Get the grid changes since the previous call, for incremental rendering
+/// 
+/// **Learning Point**: The state keeps a snapshot of the grid as of the last call
+/// (what the renderer already has). Each call diffs the live grid against it, reports
+/// tiles that were added, changed type, or were removed, and then replaces the snapshot.
+/// The first call reports every tile as added. A renderer that applies each patch stays
+/// in sync without re-uploading the whole grid every frame.
+/// 
+/// @returns JSON object with lists sorted by (q, r):
+///          {"added":[{"q":0,"r":0,"tileType":N},...],"changed":[{"q":0,"r":0,"tileType":N},...],"removed":[{"q":0,"r":0},...]}
+#[wasm_bindgen]
+pub fn get_grid_patch() -> String {
+    let patch = lock_or_recover(&WFC_STATE).take_grid_patch();
+    
+    let tiles_to_json = |tiles: &[((i32, i32), TileType)]| -> String {
+        let parts: Vec<String> = tiles.iter()
+            .map(|((q, r), tile_type)| format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32))
+            .collect();
+        format!("[{}]", parts.join(","))
+    };
+    
+    let removed_parts: Vec<String> = patch.removed.iter()
+        .map(|(q, r)| format!(r#"{{"q":{},"r":{}}}"#, q, r))
+        .collect();
+    
+    format!(
+        r#"{{"added":{},"changed":{},"removed":[{}]}}"#,
+        tiles_to_json(&patch.added),
+        tiles_to_json(&patch.changed),
+        removed_parts.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn painted_roads_must_be_connected() {
+        let _guard = lock_or_recover(&crate::state::STATE_TEST_LOCK);
+        clear_pre_constraints();
+        
+        // Two road segments with a one-tile gap at (0,0)
+        for q in [-2, -1, 1, 2] {
+            set_pre_constraint(q, 0, TileType::Road as i32);
+        }
+        set_pre_constraint(0, 0, TileType::Grass as i32);
+        assert!(!validate_constraint_roads_connected());
+        
+        set_pre_constraint(0, 0, TileType::Road as i32);
+        assert!(validate_constraint_roads_connected());
+        
+        clear_pre_constraints();
+    }
+    
+    #[test]
+    fn export_grid_2d_lays_out_three_hexes() {
+        let _guard = lock_or_recover(&crate::state::STATE_TEST_LOCK);
+        {
+            let mut state = lock_or_recover(&WFC_STATE);
+            state.clear();
+            state.insert_tile(0, 0, TileType::Grass);
+            state.insert_tile(1, 0, TileType::Road);
+            state.insert_tile(0, 1, TileType::Water);
+        }
+        
+        // odd-r keeps (0,1) under (0,0); even-r shifts it right, leaving a gap at the row start
+        assert_eq!(export_grid_2d(0), r#"{"originCol":0,"originRow":0,"rows":[[0,2],[4,-1]]}"#);
+        assert_eq!(export_grid_2d(1), r#"{"originCol":0,"originRow":0,"rows":[[0,2],[-1,4]]}"#);
+        assert_eq!(export_grid_2d(4), "null");
+        
+        clear_layout();
+    }
+    
+    #[test]
+    fn applying_the_inverse_edits_restores_the_grid() {
+        let _guard = lock_or_recover(&crate::state::STATE_TEST_LOCK);
+        let snapshot = || {
+            let mut tiles: Vec<((i32, i32), TileType)> = lock_or_recover(&WFC_STATE).grid_tiles().collect();
+            tiles.sort_by_key(|(key, _)| *key);
+            tiles
+        };
+        {
+            let mut state = lock_or_recover(&WFC_STATE);
+            state.clear();
+            state.insert_tile(0, 0, TileType::Grass);
+            state.insert_tile(1, 0, TileType::Road);
+            state.insert_tile(0, 1, TileType::Water);
+        }
+        let original = snapshot();
+        
+        // Change, remove, add, and edit the same cell twice
+        let inverse = apply_edits(r#"[{"q":0,"r":0,"tileType":3},{"q":1,"r":0,"tileType":-1},{"q":5,"r":5,"tileType":1},{"q":0,"r":0,"tileType":4}]"#.to_string());
+        assert_ne!(snapshot(), original);
+        
+        apply_edits(inverse);
+        assert_eq!(snapshot(), original);
+        
+        clear_layout();
+    }
+    
+    #[test]
+    fn grid_patch_reports_exactly_one_change() {
+        let _guard = lock_or_recover(&crate::state::STATE_TEST_LOCK);
+        clear_pre_constraints();
+        set_pre_constraint(0, 0, TileType::Grass as i32);
+        set_pre_constraint(1, 0, TileType::Road as i32);
+        set_pre_constraint(0, 1, TileType::Water as i32);
+        generate_layout();
+        get_grid_patch();
+        
+        apply_edits(r#"[{"q":1,"r":0,"tileType":3}]"#.to_string());
+        assert_eq!(get_grid_patch(), r#"{"added":[],"changed":[{"q":1,"r":0,"tileType":3}],"removed":[]}"#);
+        assert_eq!(get_grid_patch(), r#"{"added":[],"changed":[],"removed":[]}"#);
+        
+        clear_pre_constraints();
+        clear_layout();
+    }
+    
+    #[test]
+    fn symmetric_layout_matches_at_rotated_positions() {
+        let _guard = lock_or_recover(&crate::state::STATE_TEST_LOCK);
+        // Random tiles on a radius-4 hexagon centered off the origin
+        let tiles: Vec<String> = crate::hex_utils::generate_hex_grid(4, 3, -2).iter()
+            .map(|hex| {
+                let tile_type = (crate::utils::coord_random(5, hex.q, hex.r) * TileType::COUNT as f64) as i32;
+                format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, hex.q, hex.r, tile_type)
+            })
+            .collect();
+        let layout_json = format!("[{}]", tiles.join(","));
+        
+        for (symmetry, step) in [(2, 3), (6, 1)] {
+            import_layout_json(layout_json.clone());
+            assert!(apply_symmetry(symmetry) > 0);
+            let grid: HashMap<(i32, i32), TileType> = parse_tile_map_json(&export_layout_json()).into_iter().collect();
+            assert_eq!(grid.len(), 61);
+            
+            for (&(q, r), &tile_type) in &grid {
+                let rotated = cube_rotate(axial_to_cube(q - 3, r + 2), step);
+                assert_eq!(grid[&(rotated.q + 3, rotated.r - 2)], tile_type, "symmetry {} at ({}, {})", symmetry, q, r);
+            }
+            // Already symmetric: a second pass changes nothing
+            assert_eq!(apply_symmetry(symmetry), 0);
+        }
+        assert_eq!(apply_symmetry(3), -1);
+        
+        clear_layout();
+    }
+}
@@ -9,7 +9,15 @@
 /// - layout: WFC layout generation
 /// - roads: Road network generation
 /// - chunks: Chunk management
+/// - fov: Field of view (shadow-casting)
+/// - tilemap: Tile map transformations
+/// - regions: Region analysis
+/// - terrain: Walkable terrain analysis
+/// - patterns: Deterministic pattern maps
+/// - elevation: Elevation drainage, steepness and elevation-based terrain
+/// - pipeline: Combined map generation pipeline
 /// - utils: Utility functions
+/// - test_util: Shared unit test fixtures
 
 // Module declarations
 mod types;
@@ -20,25 +28,55 @@ mod voronoi;
 mod layout;
 mod roads;
 mod chunks;
+mod fov;
+mod tilemap;
+mod regions;
+mod terrain;
+mod patterns;
+mod elevation;
+mod pipeline;
 mod utils;
+#[cfg(test)]
+mod test_util;
 
 // Re-export all public functions from sub-modules
 // This maintains the same public API as before the refactoring
 
 // From layout module
-pub use layout::{init, get_wasm_version, generate_layout, get_tile_at, clear_layout, set_pre_constraint, clear_pre_constraints, get_stats};
+pub use layout::{init, get_wasm_version, generate_layout, get_tile_at, clear_layout, set_pre_constraint, clear_pre_constraints, set_pre_constraints_json, get_pre_constraints_json, validate_constraint_roads_connected, get_stats, get_tiles_of_type, nearest_tile_of_type, tiles_within_range, apply_symmetry, smooth_tiles, export_layout_json, import_layout_json, export_grid_2d, apply_edits, get_grid_patch};
 
 // From astar module
-pub use astar::{hex_astar, build_path_between_roads, validate_road_connectivity};
+pub use astar::{hex_astar, hex_astar_result, hex_astar_packed, hex_astar_weighted, hex_astar_with_cost, hex_astar_stats, hex_astar_blocked, hex_astar_avoiding, hex_astar_smooth, hex_astar_bidirectional, build_path_between_roads, validate_road_connectivity, road_components_json, hex_distance_matrix, path_self_intersects, hex_path_via};
 
 // From voronoi module
-pub use voronoi::generate_voronoi_regions;
+pub use voronoi::{generate_voronoi_regions, generate_voronoi_regions_relaxed, generate_voronoi_regions_packed, generate_voronoi_from_seeds};
 
 // From roads module
-pub use roads::generate_road_network_growing_tree;
+pub use roads::{generate_road_network_growing_tree, generate_road_network_weighted, generate_road_network_mst, generate_road_network_with_loops, find_bridge_sites, road_topology_json, road_network_metrics};
 
 // From chunks module
 pub use chunks::{calculate_chunk_radius, calculate_chunk_neighbors, find_nearest_neighbor_chunk, disable_distant_chunks, calculate_chunk_for_tile};
 
+// From fov module
+pub use fov::compute_fov;
+
+// From tilemap module
+pub use tilemap::{blend_tile_maps, grid_morton_order, erode_coastline, detect_coastline, translate_tile_map, downsample_grid};
+
+// From regions module
+pub use regions::{region_medoid, region_polygons, bounding_layer, territory_hull, region_stats, region_compactness};
+
+// From terrain module
+pub use terrain::{find_dead_ends, find_dead_end_corridors, distance_to_boundary, nearest_free_tile, accessibility_ratio};
+
+// From patterns module
+pub use patterns::{generate_ring_test_grid, generate_spiral_pattern, generate_hex_grid_noisy, brush_stamp, generate_sample_map};
+
+// From elevation module
+pub use elevation::{compute_flow_directions, trace_rivers, generate_river, compute_slopes, generate_terrain_from_elevation};
+
+// From pipeline module
+pub use pipeline::generate_map;
+
 // From utils module
-pub use utils::{batch_get_tile_types, shuffle_array, count_adjacent_roads, get_adjacent_valid_terrain, generate_building_placement, batch_hex_to_world};
+pub use utils::{batch_get_tile_types, shuffle_array, count_adjacent_roads, get_adjacent_valid_terrain, generate_building_placement, batch_hex_to_world, all_connection_masks, hex_distance_js, hex_neighbors_json, hex_ring_json, hex_spiral_json, hex_line_json, hex_to_pixel, pixel_to_hex};
@@ -0,0 +1,300 @@
+//! Pattern map generation module
+//!
+//! Deterministic, known-pattern maps in the pre-constraint format
+//! (`[{"q":0,"r":0,"tileType":0},...]`) for testing renderers and stylized levels,
+//! plus seeded map footprints and editor brush shapes (`[{"q":0,"r":0},...]`).
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::types::TileType;
+use crate::hex_utils::{generate_hex_grid, hex_distance, hex_spiral, axial_to_cube, cube_ring, get_hex_neighbors, tile_map_to_json};
+use crate::utils::coord_random;
+
+/// Number of tile types the pattern generators cycle through
+const PATTERN_TILE_TYPES: i32 = 5;
+
+/// Sample map 0: a small town - crossing roads, houses at the corners, a lake and a grove
+const SAMPLE_TOWN: [(i32, i32, i32); 37] = [
+    (-3, 0, 2), (-3, 1, 0), (-3, 2, 3), (-3, 3, 3), (-2, -1, 0), (-2, 0, 2),
+    (-2, 1, 0), (-2, 2, 3), (-2, 3, 3), (-1, -2, 0), (-1, -1, 1), (-1, 0, 2),
+    (-1, 1, 1), (-1, 2, 0), (-1, 3, 0), (0, -3, 2), (0, -2, 2), (0, -1, 2),
+    (0, 0, 2), (0, 1, 2), (0, 2, 2), (0, 3, 2), (1, -3, 0), (1, -2, 0),
+    (1, -1, 1), (1, 0, 2), (1, 1, 1), (1, 2, 0), (2, -3, 0), (2, -2, 4),
+    (2, -1, 4), (2, 0, 2), (2, 1, 0), (3, -3, 4), (3, -2, 4), (3, -1, 0),
+    (3, 0, 2),
+];
+
+/// Sample map 1: an island - water ring, grass shore, forested center with a hut and a short road
+const SAMPLE_ISLAND: [(i32, i32, i32); 37] = [
+    (-3, 0, 4), (-3, 1, 4), (-3, 2, 4), (-3, 3, 4), (-2, -1, 4), (-2, 0, 0),
+    (-2, 1, 0), (-2, 2, 0), (-2, 3, 4), (-1, -2, 4), (-1, -1, 0), (-1, 0, 3),
+    (-1, 1, 3), (-1, 2, 0), (-1, 3, 4), (0, -3, 4), (0, -2, 0), (0, -1, 3),
+    (0, 0, 3), (0, 1, 3), (0, 2, 0), (0, 3, 4), (1, -3, 4), (1, -2, 0),
+    (1, -1, 3), (1, 0, 1), (1, 1, 2), (1, 2, 4), (2, -3, 4), (2, -2, 0),
+    (2, -1, 0), (2, 0, 2), (2, 1, 4), (3, -3, 4), (3, -2, 4), (3, -1, 4),
+    (3, 0, 4),
+];
+
+/// Generate concentric ring-colored test grid for renderer debugging
+/// 
+/// **Learning Point**: Each hex gets tile type `ring % 5`, where ring is its
+/// `hex_distance` from the center. The result is a set of visible concentric rings,
+/// so any coordinate mapping bug (swapped axes, wrong orientation, off-by-one offsets)
+/// shows up immediately as broken rings in the rendered output.
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"tileType":0},...], or "[]" if max_layer is negative
+#[wasm_bindgen]
+pub fn generate_ring_test_grid(max_layer: i32, center_q: i32, center_r: i32) -> String {
+    let mut tiles: HashMap<(i32, i32), TileType> = HashMap::new();
+    
+    for hex in generate_hex_grid(max_layer, center_q, center_r) {
+        let ring = hex_distance(center_q, center_r, hex.q, hex.r);
+        if let Some(tile_type) = TileType::from_i32(ring % PATTERN_TILE_TYPES) {
+            tiles.insert((hex.q, hex.r), tile_type);
+        }
+    }
+    
+    tile_map_to_json(&tiles)
+}
+
+/// Generate a banded spiral pattern for stylized levels and tests
+/// 
+/// **Learning Point**: Walks the hexagon in spiral order (center, then each ring
+/// outward) and assigns tile type `(spiral_index / period) % 5`, so every run of
+/// `period` consecutive spiral tiles shares a type. Output keeps spiral order, which
+/// makes the bands easy to check: the first `period` entries are type 0, the next
+/// `period` are type 1, and so on.
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param period - Number of consecutive spiral tiles per band (must be >= 1)
+/// @returns JSON array in spiral order: [{"q":0,"r":0,"tileType":0},...], or "null" if period < 1
+#[wasm_bindgen]
+pub fn generate_spiral_pattern(max_layer: i32, center_q: i32, center_r: i32, period: i32) -> String {
+    if period < 1 {
+        return "null".to_string();
+    }
+    
+    let spiral = hex_spiral(axial_to_cube(center_q, center_r), max_layer);
+    
+    let mut json_parts = Vec::new();
+    for (index, cube) in spiral.iter().enumerate() {
+        let band = (index / period as usize) as i32;
+        let tile_type = band % PATTERN_TILE_TYPES;
+        json_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, cube.q, cube.r, tile_type));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Generate a hexagon footprint with ragged, organic-looking borders
+/// 
+/// **Learning Point**: Only the outermost ring is touched, so the interior hexagon
+/// stays whole and connected:
+/// 1. Each boundary-ring tile is removed when its seeded roll is below `roughness / 2`.
+///    Every ring tile touches the ring inside it, so removals never split the map.
+/// 2. Each tile just outside the hexagon is added when its roll is below `roughness / 2`
+///    and it touches a boundary tile that survived step 1.
+/// 
+/// Rolls come from `coord_random`, so the same seed always gives the same footprint.
+/// A single-tile map (max_layer 0) never loses its center.
+/// 
+/// @param max_layer - Maximum layer of the base hexagon
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param roughness - Border noise, clamped to [0, 1] (0 = clean hexagon, 1 = very ragged)
+/// @param seed - Seed for the per-tile rolls
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0},...], or "[]" if max_layer is negative
+#[wasm_bindgen]
+pub fn generate_hex_grid_noisy(max_layer: i32, center_q: i32, center_r: i32, roughness: f64, seed: u32) -> String {
+    let roughness = if roughness.is_nan() { 0.0 } else { roughness.clamp(0.0, 1.0) };
+    let threshold = roughness / 2.0;
+    
+    let mut tiles: HashSet<(i32, i32)> = generate_hex_grid(max_layer, center_q, center_r)
+        .iter()
+        .map(|hex| (hex.q, hex.r))
+        .collect();
+    
+    if max_layer >= 1 {
+        let center = axial_to_cube(center_q, center_r);
+        
+        for hex in cube_ring(center, max_layer) {
+            if coord_random(seed, hex.q, hex.r) < threshold {
+                tiles.remove(&(hex.q, hex.r));
+            }
+        }
+        
+        let mut added = Vec::new();
+        for hex in cube_ring(center, max_layer + 1) {
+            let touches_map = get_hex_neighbors(hex.q, hex.r)
+                .iter()
+                .any(|neighbor| tiles.contains(neighbor));
+            if touches_map && coord_random(seed, hex.q, hex.r) < threshold {
+                added.push((hex.q, hex.r));
+            }
+        }
+        tiles.extend(added);
+    }
+    
+    let mut sorted: Vec<(i32, i32)> = tiles.into_iter().collect();
+    sorted.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in sorted {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Compute the hexes covered by an editor brush around a cursor
+/// 
+/// **Learning Point**: Centralizes brush geometry so every editor tool paints the
+/// same shapes:
+/// - 0 = single: just the cursor hex (`size` is ignored)
+/// - 1 = disk: every hex within `size` steps (`generate_hex_grid`), 3n² + 3n + 1 hexes
+/// - 2 = ring: hexes exactly `size` steps away (`cube_ring`), 6n hexes (1 for size 0)
+/// - 3 = line: `size` hexes along the q axis (horizontal for pointy-top), centered on
+///   the cursor; even lengths extend one extra hex toward +q
+/// 
+/// @param center_q - Cursor q coordinate
+/// @param center_r - Cursor r coordinate
+/// @param shape - Brush shape (0-3, see above)
+/// @param size - Brush radius (disk, ring) or length (line)
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0},...], "[]" for a negative size
+///          (or a line shorter than 1), or "null" if shape is invalid
+#[wasm_bindgen]
+pub fn brush_stamp(center_q: i32, center_r: i32, shape: i32, size: i32) -> String {
+    let mut hexes: Vec<(i32, i32)> = match shape {
+        0 => vec![(center_q, center_r)],
+        1 => generate_hex_grid(size, center_q, center_r)
+            .iter()
+            .map(|hex| (hex.q, hex.r))
+            .collect(),
+        2 => cube_ring(axial_to_cube(center_q, center_r), size)
+            .iter()
+            .map(|hex| (hex.q, hex.r))
+            .collect(),
+        3 => {
+            let start = -(size - 1) / 2;
+            (0..size.max(0)).map(|i| (center_q + start + i, center_r)).collect()
+        },
+        _ => return "null".to_string(),
+    };
+    hexes.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in hexes {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Get a fixed, hand-made sample map for snapshot and integration tests
+/// 
+/// **Learning Point**: These are curated fixtures, not generator output, so they never
+/// change when the generators evolve. Treat them as versioned: a new or different map
+/// gets a new variant number instead of editing an existing one, so downstream snapshot
+/// tests stay valid.
+/// - 0 = small town (37 tiles): crossing roads, houses, a lake and a grove
+/// - 1 = island (37 tiles): water ring, grass shore, forested center with a hut and a short road
+/// 
+/// @param variant - Sample map variant (0 or 1)
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"tileType":N},...], or "[]" for unknown variants
+#[wasm_bindgen]
+pub fn generate_sample_map(variant: i32) -> String {
+    let tiles: &[(i32, i32, i32)] = match variant {
+        0 => &SAMPLE_TOWN,
+        1 => &SAMPLE_ISLAND,
+        _ => &[],
+    };
+    
+    let mut json_parts = Vec::new();
+    for &(q, r, tile_type) in tiles {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, tile_type));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_utils::{connected_components, parse_path_json, parse_tile_map_json};
+    
+    #[test]
+    fn ring_test_grid_types_follow_rings() {
+        let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&generate_ring_test_grid(3, 2, -1))
+            .into_iter()
+            .collect();
+        assert_eq!(tiles[&(2, -1)] as i32, 0);
+        for (q, r) in get_hex_neighbors(2, -1) {
+            assert_eq!(tiles[&(q, r)] as i32, 1);
+        }
+    }
+    
+    #[test]
+    fn spiral_pattern_bands_by_period() {
+        let period = 4;
+        let types: Vec<i32> = parse_tile_map_json(&generate_spiral_pattern(3, 0, 0, period))
+            .into_iter()
+            .map(|(_, tile_type)| tile_type as i32)
+            .collect();
+        assert!(types[..period as usize].iter().all(|&tile_type| tile_type == 0));
+        assert!(types[period as usize..2 * period as usize].iter().all(|&tile_type| tile_type == 1));
+        assert_eq!(types[2 * period as usize], 2);
+    }
+    
+    #[test]
+    fn noisy_grid_is_exact_at_zero_and_connected_when_rough() {
+        let mut clean: Vec<(i32, i32)> = generate_hex_grid(5, 1, -2).iter().map(|hex| (hex.q, hex.r)).collect();
+        clean.sort();
+        for seed in [0, 3, 99] {
+            assert_eq!(parse_path_json(&generate_hex_grid_noisy(5, 1, -2, 0.0, seed)), clean);
+            
+            for roughness in [0.5, 1.0] {
+                let noisy: HashSet<(i32, i32)> = parse_path_json(&generate_hex_grid_noisy(5, 1, -2, roughness, seed)).into_iter().collect();
+                assert_eq!(connected_components(&noisy).len(), 1, "seed {} roughness {}", seed, roughness);
+            }
+        }
+    }
+    
+    #[test]
+    fn brush_shapes_have_expected_counts() {
+        let count = |shape: i32, size: i32| parse_path_json(&brush_stamp(3, -1, shape, size)).len();
+        
+        // Single ignores size
+        assert_eq!(count(0, 0), 1);
+        assert_eq!(count(0, 4), 1);
+        // Disk: 3n² + 3n + 1
+        for n in 0..4 {
+            assert_eq!(count(1, n), (3 * n * n + 3 * n + 1) as usize);
+        }
+        // Ring: 6n, or 1 for size 0
+        assert_eq!(count(2, 0), 1);
+        for n in 1..4 {
+            assert_eq!(count(2, n), (6 * n) as usize);
+        }
+        // Line: size hexes
+        for n in 1..6 {
+            assert_eq!(count(3, n), n as usize);
+        }
+        assert_eq!(brush_stamp(0, 0, 4, 1), "null");
+    }
+    
+    #[test]
+    fn sample_maps_have_fixed_sizes_and_round_trip() {
+        for (variant, tile_count) in [(0, 37), (1, 37)] {
+            let sample = generate_sample_map(variant);
+            let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&sample).into_iter().collect();
+            assert_eq!(tiles.len(), tile_count);
+            assert_eq!(tile_map_to_json(&tiles), sample);
+        }
+        assert_eq!(generate_sample_map(2), "[]");
+    }
+}
@@ -0,0 +1,117 @@
+//! Combined map generation pipeline module
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet};
+use crate::state::{WFC_STATE, lock_or_recover};
+use crate::types::{DistanceMetric, GridShape, TileType};
+use crate::hex_utils::{parse_int_fields_json, tile_map_to_json};
+use crate::voronoi::voronoi_tiles;
+use crate::roads::grow_road_network;
+use crate::utils::coord_random;
+
+/// Read one integer field from a flat JSON config object, or `default` if it is missing
+fn config_int(config_json: &str, key: &str, default: i32) -> i32 {
+    parse_int_fields_json(config_json, &[key])
+        .first()
+        .map_or(default, |fields| fields[0])
+}
+
+/// Generate a complete chunk map in one call
+/// 
+/// **Learning Point**: Building a chunk used to take four WASM calls with JSON marshalled
+/// between each: `generate_voronoi_regions`, `set_pre_constraints_json`,
+/// `generate_layout` and `generate_road_network_growing_tree`. This runs the same
+/// pipeline in Rust:
+/// 1. Voronoi regions, with seeds placed by `seed`, assign every hex a terrain type
+/// 2. Road seeds are drawn from the grass and forest tiles (water, mountain and sand
+///    never carry roads) with a Fisher-Yates shuffle driven by `seed`
+/// 3. The growing tree connects the seeds and expands to `roadTarget` tiles
+/// 4. Road tiles overwrite their terrain, and the result replaces the pre-constraints
+///    and the generated layout, exactly as the separate calls would have left them
+/// 
+/// The same config always produces the same map.
+/// 
+/// Config keys (all optional integers):
+/// - forestSeeds, waterSeeds, grassSeeds, mountainSeeds, sandSeeds - Voronoi seed counts
+///   (defaults 4, 2, 6, 0, 0)
+/// - relaxationIterations - Lloyd's relaxation passes (default 0)
+/// - metric - Voronoi distance metric, as in `generate_voronoi_regions` (default 0)
+/// - shape - Grid outline, as in `generate_voronoi_regions` (default 0, hexagon)
+/// - roadTarget - Road tile count to grow to (default 10% of the grass and forest tiles;
+///   0 disables roads)
+/// - seed - PRNG seed for Voronoi seed placement and road seed selection (default 0)
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param config_json - JSON config object: {"forestSeeds":4,"waterSeeds":2,"roadTarget":40,"seed":7,...}
+/// @returns JSON array of the final tiles sorted by (q, r): [{"q":0,"r":0,"tileType":3},...],
+///          or "null" if metric or shape is invalid
+#[wasm_bindgen]
+pub fn generate_map(max_layer: i32, center_q: i32, center_r: i32, config_json: String) -> String {
+    let seed = config_int(&config_json, "seed", 0) as u32;
+    let metric = match DistanceMetric::from_i32(config_int(&config_json, "metric", 0)) {
+        Some(metric) => metric,
+        None => return "null".to_string(),
+    };
+    let shape = match GridShape::from_i32(config_int(&config_json, "shape", 0)) {
+        Some(shape) => shape,
+        None => return "null".to_string(),
+    };
+    
+    // Step 1: Voronoi terrain
+    let mut tiles: HashMap<(i32, i32), TileType> = voronoi_tiles(
+        max_layer,
+        center_q,
+        center_r,
+        config_int(&config_json, "forestSeeds", 4),
+        config_int(&config_json, "waterSeeds", 2),
+        config_int(&config_json, "grassSeeds", 6),
+        config_int(&config_json, "mountainSeeds", 0),
+        config_int(&config_json, "sandSeeds", 0),
+        config_int(&config_json, "relaxationIterations", 0),
+        metric,
+        shape,
+        seed,
+    )
+    .into_iter()
+    .collect();
+    
+    // Step 2: Road seeds from the road-capable terrain, in a seeded random order
+    let mut road_terrain: Vec<(i32, i32)> = tiles.iter()
+        .filter(|(_, tile_type)| matches!(tile_type, TileType::Grass | TileType::Forest))
+        .map(|(key, _)| *key)
+        .collect();
+    road_terrain.sort();
+    
+    let default_target = (road_terrain.len() / 10) as i32;
+    let road_target = config_int(&config_json, "roadTarget", default_target);
+    
+    if road_target > 0 {
+        for i in (1..road_terrain.len()).rev() {
+            let j = (coord_random(seed, i as i32, 0) * (i + 1) as f64) as usize;
+            road_terrain.swap(i, j);
+        }
+        
+        // A quarter of the target as seeds, like the TypeScript generator
+        let seed_count = ((road_target / 4).max(1) as usize).min(road_terrain.len());
+        let road_seeds = &road_terrain[..seed_count];
+        let walkable: HashSet<(i32, i32)> = road_terrain.iter().copied().collect();
+        
+        // Step 3: Growing-tree road network
+        for road in grow_road_network(road_seeds, &walkable, road_target) {
+            tiles.insert(road, TileType::Road);
+        }
+    }
+    
+    // Step 4: Store as pre-constraints and generated layout
+    let mut state = lock_or_recover(&WFC_STATE);
+    state.clear_pre_constraints();
+    state.clear();
+    for (&(q, r), &tile_type) in &tiles {
+        state.set_pre_constraint(q, r, tile_type);
+        state.insert_tile(q, r, tile_type);
+    }
+    
+    tile_map_to_json(&tiles)
+}
@@ -0,0 +1,445 @@
+//! Region analysis module
+//!
+//! Functions here work on sets of hexes that form a region (a biome, a territory,
+//! a connected group of same-type tiles) and summarize or describe them.
+
+use wasm_bindgen::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::types::{HexOrientation, TileType};
+use crate::hex_utils::{parse_path_json, parse_valid_terrain_json, parse_tile_map_json, generate_hex_grid, hex_distance, hex_corners, get_hex_neighbors, CUBE_DIRECTIONS};
+use crate::utils::fmt_f64;
+
+/// Maximum number of members summed per candidate in `region_medoid`
+/// Larger regions sum over an evenly strided sample of this many members
+const MEDOID_SAMPLE_SIZE: usize = 256;
+
+/// Find the medoid of a region: the member hex closest to all other members
+/// 
+/// **Learning Point**: Unlike a centroid, the medoid is always one of the input hexes,
+/// so it lands inside crescent or ring shaped regions where the geometric center
+/// would fall outside. Every member is a candidate, scored by the sum of `hex_distance`
+/// to the other members. For regions larger than 256 hexes the sum is taken over an
+/// evenly strided sample of 256 members, which keeps the cost at O(n * 256) while
+/// still picking a central tile. Ties go to the smallest (q, r).
+/// 
+/// @param hexes_json - JSON array of region hexes: [{"q":0,"r":0},...]
+/// @returns JSON object {"q":0,"r":0}, or "null" for empty input
+#[wasm_bindgen]
+pub fn region_medoid(hexes_json: String) -> String {
+    let mut hexes = parse_path_json(&hexes_json);
+    hexes.sort();
+    hexes.dedup();
+    
+    if hexes.is_empty() {
+        return "null".to_string();
+    }
+    
+    let stride = hexes.len().div_ceil(MEDOID_SAMPLE_SIZE);
+    let sample: Vec<(i32, i32)> = hexes.iter().step_by(stride).copied().collect();
+    
+    let mut best = hexes[0];
+    let mut best_sum = i64::MAX;
+    for &(q, r) in &hexes {
+        let sum: i64 = sample.iter()
+            .map(|&(sq, sr)| hex_distance(q, r, sq, sr) as i64)
+            .sum();
+        if sum < best_sum {
+            best_sum = sum;
+            best = (q, r);
+        }
+    }
+    
+    format!(r#"{{"q":{},"r":{}}}"#, best.0, best.1)
+}
+
+/// Label connected same-type regions of a tile map
+/// 
+/// **Learning Point**: Flood fills (BFS) from each unvisited tile over neighbors with
+/// the same tile type. Regions are returned in order of their smallest (q, r) member,
+/// with members sorted, so labels are stable for a given map.
+pub fn label_regions(tiles: &HashMap<(i32, i32), TileType>) -> Vec<(TileType, Vec<(i32, i32)>)> {
+    let mut keys: Vec<(i32, i32)> = tiles.keys().copied().collect();
+    keys.sort();
+    
+    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    let mut regions = Vec::new();
+    
+    for start in keys {
+        if visited.contains(&start) {
+            continue;
+        }
+        
+        let tile_type = tiles[&start];
+        let mut members = Vec::new();
+        let mut queue = VecDeque::new();
+        visited.insert(start);
+        queue.push_back(start);
+        
+        while let Some((q, r)) = queue.pop_front() {
+            members.push((q, r));
+            for neighbor in get_hex_neighbors(q, r) {
+                if tiles.get(&neighbor) == Some(&tile_type) && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        
+        members.sort();
+        regions.push((tile_type, members));
+    }
+    
+    regions
+}
+
+/// Get the outline of a region as boundary edges
+/// Each edge is (tile, direction) where the neighbor in `CUBE_DIRECTIONS[direction]`
+/// is outside the region. Edges are ordered by tile, then direction.
+pub fn region_boundary_edges(members: &HashSet<(i32, i32)>) -> Vec<((i32, i32), usize)> {
+    let mut sorted: Vec<(i32, i32)> = members.iter().copied().collect();
+    sorted.sort();
+    
+    let mut edges = Vec::new();
+    for (q, r) in sorted {
+        for (direction, offset) in CUBE_DIRECTIONS.iter().enumerate() {
+            if !members.contains(&(q + offset.q, r + offset.r)) {
+                edges.push(((q, r), direction));
+            }
+        }
+    }
+    edges
+}
+
+/// Exact integer key for a hex corner, shared by the (up to) 3 hexes that meet there
+/// Uses a lattice where the hex center is (2q + r, 3r) and corner offsets are whole steps
+fn corner_key(q: i32, r: i32, corner: usize) -> (i32, i32) {
+    const CORNER_OFFSETS: [(i32, i32); 6] = [(1, -1), (1, 1), (0, 2), (-1, 1), (-1, -1), (0, -2)];
+    let (dx, dy) = CORNER_OFFSETS[corner];
+    (2 * q + r + dx, 3 * r + dy)
+}
+
+/// Outgoing boundary edge: (end corner key, start corner pixel position)
+type CornerEdge = ((i32, i32), (f64, f64));
+
+/// Chain a region's boundary edges into closed rings of corner points
+/// Every tile's edges run corner i -> i + 1, so shared interior edges never appear and
+/// the outer boundary and any holes each close into their own ring.
+fn chain_boundary_rings(
+    members: &HashSet<(i32, i32)>,
+    size: f64,
+    orientation: HexOrientation,
+) -> Vec<Vec<(f64, f64)>> {
+    let mut outgoing: HashMap<(i32, i32), Vec<CornerEdge>> = HashMap::new();
+    for ((q, r), direction) in region_boundary_edges(members) {
+        let from = (6 - direction) % 6;
+        let to = (7 - direction) % 6;
+        let corners = hex_corners(q, r, size, orientation);
+        outgoing.entry(corner_key(q, r, from))
+            .or_default()
+            .push((corner_key(q, r, to), corners[from]));
+    }
+    
+    let mut starts: Vec<(i32, i32)> = outgoing.keys().copied().collect();
+    starts.sort();
+    
+    let mut rings = Vec::new();
+    for start in starts {
+        while outgoing.get(&start).is_some_and(|edges| !edges.is_empty()) {
+            let mut ring = Vec::new();
+            let mut current = start;
+            // Walk until the ring closes or runs out of edges
+            while let Some((next, point)) = outgoing.get_mut(&current).and_then(|edges| edges.pop()) {
+                ring.push(point);
+                current = next;
+                if current == start {
+                    break;
+                }
+            }
+            rings.push(ring);
+        }
+    }
+    
+    rings
+}
+
+/// Absolute polygon area via the shoelace formula
+fn polygon_area(points: &[(f64, f64)]) -> f64 {
+    let mut twice_area = 0.0;
+    for i in 0..points.len() {
+        let (x1, y1) = points[i];
+        let (x2, y2) = points[(i + 1) % points.len()];
+        twice_area += x1 * y2 - x2 * y1;
+    }
+    (twice_area / 2.0).abs()
+}
+
+/// Merge adjacent same-type tiles into polygon outlines for vector export
+/// 
+/// **Learning Point**: Three steps:
+/// 1. Label connected same-type regions
+/// 2. Collect each region's boundary edges (hex sides facing outside the region)
+/// 3. Chain the edges corner to corner into closed rings in pixel space
+/// 
+/// A region with holes produces several rings; only the outer ring (largest area)
+/// is emitted for now. Points are not repeated - the polygon closes implicitly.
+/// Pixel positions follow `hex_corners` (y grows down).
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @param size - Hex size (center to corner) in pixels
+/// @param orientation - 0 = pointy-top, 1 = flat-top
+/// @returns JSON array: [{"tileType":0,"polygon":[{"x":0,"y":0},...]},...], or "null" if orientation is invalid
+#[wasm_bindgen]
+pub fn region_polygons(tiles_json: String, size: f64, orientation: i32) -> String {
+    let orientation = match HexOrientation::from_i32(orientation) {
+        Some(orientation) => orientation,
+        None => return "null".to_string(),
+    };
+    
+    let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&tiles_json).into_iter().collect();
+    
+    let mut json_parts = Vec::new();
+    for (tile_type, members) in label_regions(&tiles) {
+        let member_set: HashSet<(i32, i32)> = members.into_iter().collect();
+        let rings = chain_boundary_rings(&member_set, size, orientation);
+        
+        let outer = rings.into_iter()
+            .max_by(|a, b| polygon_area(a).total_cmp(&polygon_area(b)))
+            .unwrap_or_default();
+        
+        let mut point_parts = Vec::new();
+        for (x, y) in outer {
+            point_parts.push(format!(r#"{{"x":{},"y":{}}}"#, fmt_f64(x), fmt_f64(y)));
+        }
+        
+        json_parts.push(format!(
+            r#"{{"tileType":{},"polygon":[{}]}}"#,
+            tile_type as i32, point_parts.join(",")
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Find the smallest hexagon layer around a center that contains every tile
+/// 
+/// **Learning Point**: The hexagon analog of a bounding box - the result is the
+/// largest `hex_distance` from the center to any tile, so passing it as `max_layer`
+/// to the grid generators produces a hexagon that covers the whole set.
+/// 
+/// @param tiles_json - JSON array of hexes: [{"q":0,"r":0},...] (extra fields are ignored)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @returns Bounding layer, or -1 for empty input
+#[wasm_bindgen]
+pub fn bounding_layer(tiles_json: String, center_q: i32, center_r: i32) -> i32 {
+    parse_valid_terrain_json(&tiles_json)
+        .iter()
+        .map(|&(q, r)| hex_distance(center_q, center_r, q, r))
+        .max()
+        .unwrap_or(-1)
+}
+
+/// Approximate a territory claim around scattered markers and return its outline
+/// 
+/// **Learning Point**: The claimed region is every hex within `fill_radius` steps of
+/// the nearest marker - the union of a small hexagon around each marker. Markers closer
+/// than `2 * fill_radius + 1` apart merge into one region, so a radius a little over half
+/// the marker spacing fences them in together. The outline is the region's boundary
+/// edges: hex sides whose neighbor in `CUBE_DIRECTIONS[direction]` is unclaimed.
+/// 
+/// @param markers_json - JSON array of marker hexes: [{"q":0,"r":0},...]
+/// @param fill_radius - Claim radius around each marker in hex steps (must be >= 0)
+/// @returns JSON array of boundary edges sorted by tile, then direction:
+///          [{"q":0,"r":0,"direction":0},...], or "null" if fill_radius is negative
+#[wasm_bindgen]
+pub fn territory_hull(markers_json: String, fill_radius: i32) -> String {
+    if fill_radius < 0 {
+        return "null".to_string();
+    }
+    
+    let mut claimed: HashSet<(i32, i32)> = HashSet::new();
+    for (q, r) in parse_valid_terrain_json(&markers_json) {
+        for hex in generate_hex_grid(fill_radius, q, r) {
+            claimed.insert((hex.q, hex.r));
+        }
+    }
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), direction) in region_boundary_edges(&claimed) {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"direction":{}}}"#, q, r, direction));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Summarize every connected same-type region of a tile map
+/// 
+/// **Learning Point**: Uses the same labeling as `region_polygons`, so region ids match
+/// between the two. The centroid is the mean axial coordinate of the members and may
+/// be fractional (or fall outside a crescent-shaped region - use `region_medoid` when
+/// a member tile is needed). The table is handy for balancing: many tiny regions of
+/// one type usually means the generator is too noisy.
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @returns JSON array ordered by region id:
+///          [{"region":0,"tileType":0,"size":7,"centroid":{"q":0,"r":0}},...]
+#[wasm_bindgen]
+pub fn region_stats(tiles_json: String) -> String {
+    let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&tiles_json).into_iter().collect();
+    
+    let mut json_parts = Vec::new();
+    for (region, (tile_type, members)) in label_regions(&tiles).into_iter().enumerate() {
+        let size = members.len();
+        let sum_q: f64 = members.iter().map(|&(q, _)| q as f64).sum();
+        let sum_r: f64 = members.iter().map(|&(_, r)| r as f64).sum();
+        
+        json_parts.push(format!(
+            r#"{{"region":{},"tileType":{},"size":{},"centroid":{{"q":{},"r":{}}}}}"#,
+            region,
+            tile_type as i32,
+            size,
+            fmt_f64(sum_q / size as f64),
+            fmt_f64(sum_r / size as f64)
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Score how blob-like each connected same-type region is
+/// 
+/// **Learning Point**: Compactness is `boundary_edge_count / tile_count`. A single tile
+/// scores 6; a strip one tile wide approaches 4 as it grows, since each tile keeps
+/// four outside edges; a filled hexagon approaches 0 because most of its edges are
+/// shared with other members. Lower is more compact, so a map whose biomes score
+/// high is fragmented or stringy. Region ids match `region_stats`.
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @returns JSON array ordered by region id: [{"region":0,"tileType":0,"compactness":C},...]
+#[wasm_bindgen]
+pub fn region_compactness(tiles_json: String) -> String {
+    let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&tiles_json).into_iter().collect();
+    
+    let mut json_parts = Vec::new();
+    for (region, (tile_type, members)) in label_regions(&tiles).into_iter().enumerate() {
+        let tile_count = members.len();
+        let member_set: HashSet<(i32, i32)> = members.into_iter().collect();
+        let edge_count = region_boundary_edges(&member_set).len();
+        
+        json_parts.push(format!(
+            r#"{{"region":{},"tileType":{},"compactness":{}}}"#,
+            region,
+            tile_type as i32,
+            fmt_f64(edge_count as f64 / tile_count as f64)
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{coords_json, disk};
+    use crate::hex_utils::{connected_components, parse_int_fields_json, tile_map_to_json};
+    
+    #[test]
+    fn medoid_of_crescent_is_a_member() {
+        // The half of ring 3 with q >= 0: the centroid lies in the empty middle
+        let crescent: Vec<(i32, i32)> = generate_hex_grid(3, 0, 0).iter()
+            .filter(|hex| hex_distance(0, 0, hex.q, hex.r) == 3 && hex.q >= 0)
+            .map(|hex| (hex.q, hex.r))
+            .collect();
+        let n = crescent.len() as f64;
+        let centroid = (
+            (crescent.iter().map(|c| c.0).sum::<i32>() as f64 / n).round() as i32,
+            (crescent.iter().map(|c| c.1).sum::<i32>() as f64 / n).round() as i32,
+        );
+        assert!(!crescent.contains(&centroid));
+        
+        let medoid = parse_path_json(&region_medoid(coords_json(&crescent)));
+        assert_eq!(medoid.len(), 1);
+        assert!(crescent.contains(&medoid[0]), "{:?}", medoid);
+    }
+    
+    #[test]
+    fn single_hex_polygon_is_a_hexagon() {
+        for orientation in [0, 1] {
+            let polygons: serde_json::Value = serde_json::from_str(&region_polygons(
+                r#"[{"q":0,"r":0,"tileType":2}]"#.to_string(),
+                10.0,
+                orientation,
+            )).unwrap();
+            let polygons = polygons.as_array().unwrap();
+            assert_eq!(polygons.len(), 1);
+            assert_eq!(polygons[0]["tileType"], 2);
+            
+            let points = polygons[0]["polygon"].as_array().unwrap();
+            assert_eq!(points.len(), 6);
+            for point in points {
+                let (x, y) = (point["x"].as_f64().unwrap(), point["y"].as_f64().unwrap());
+                assert!((x.hypot(y) - 10.0).abs() < 1e-3, "{:?}", point);
+            }
+        }
+    }
+    
+    #[test]
+    fn bounding_layer_covers_scattered_tiles() {
+        let tiles = [(5, -2), (-1, 4), (2, 2), (-6, 1), (0, -3)];
+        let (center_q, center_r) = (1, 0);
+        let layer = bounding_layer(coords_json(&tiles), center_q, center_r);
+        
+        let farthest = tiles.iter().map(|&(q, r)| hex_distance(center_q, center_r, q, r)).max().unwrap();
+        assert_eq!(layer, farthest);
+        // Every tile lies in the hexagon of that layer (the TS isInHexagonPattern check)
+        let hexagon: HashSet<(i32, i32)> = generate_hex_grid(layer, center_q, center_r).iter().map(|hex| (hex.q, hex.r)).collect();
+        assert!(tiles.iter().all(|tile| hexagon.contains(tile)));
+        assert_eq!(bounding_layer("[]".to_string(), 0, 0), -1);
+    }
+    
+    #[test]
+    fn territory_hull_encloses_triangle_of_markers() {
+        let markers = [(0, 0), (4, 0), (0, 4)];
+        let edges = parse_int_fields_json(&territory_hull(coords_json(&markers), 2), &["q", "r", "direction"]);
+        assert!(!edges.is_empty());
+        
+        // Every edge faces an unclaimed hex, and no marker or claimed interior tile is on the outline
+        let claimed = |q: i32, r: i32| markers.iter().any(|&(mq, mr)| hex_distance(q, r, mq, mr) <= 2);
+        for fields in &edges {
+            let offset = CUBE_DIRECTIONS[fields[2] as usize];
+            assert!(claimed(fields[0], fields[1]) && !claimed(fields[0] + offset.q, fields[1] + offset.r));
+        }
+        let outline: HashSet<(i32, i32)> = edges.iter().map(|fields| (fields[0], fields[1])).collect();
+        assert!(markers.iter().all(|marker| !outline.contains(marker)));
+        assert!(!outline.contains(&(1, 1)));
+        
+        // The outline tiles form one closed loop around the region
+        assert_eq!(connected_components(&outline).len(), 1);
+    }
+    
+    #[test]
+    fn region_stats_reports_two_regions() {
+        // Three grass tiles on the left, two water tiles on the right
+        let tiles = r#"[{"q":-2,"r":0,"tileType":0},{"q":-1,"r":0,"tileType":0},{"q":-2,"r":1,"tileType":0},{"q":0,"r":0,"tileType":4},{"q":1,"r":0,"tileType":4}]"#;
+        let stats: serde_json::Value = serde_json::from_str(&region_stats(tiles.to_string())).unwrap();
+        let stats = stats.as_array().unwrap();
+        
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0]["tileType"].as_i64(), stats[0]["size"].as_i64()), (Some(0), Some(3)));
+        assert_eq!((stats[1]["tileType"].as_i64(), stats[1]["size"].as_i64()), (Some(4), Some(2)));
+    }
+    
+    #[test]
+    fn hexagon_is_more_compact_than_strip_of_equal_area() {
+        let compactness = |tiles: Vec<(i32, i32)>| -> f64 {
+            let map: HashMap<(i32, i32), TileType> = tiles.into_iter().map(|key| (key, TileType::Grass)).collect();
+            let scores: serde_json::Value = serde_json::from_str(&region_compactness(tile_map_to_json(&map))).unwrap();
+            assert_eq!(scores.as_array().unwrap().len(), 1);
+            scores[0]["compactness"].as_f64().unwrap()
+        };
+        
+        let hexagon: Vec<(i32, i32)> = disk(2);
+        let strip: Vec<(i32, i32)> = (0..hexagon.len() as i32).map(|q| (q, 0)).collect();
+        let (hexagon_score, strip_score) = (compactness(hexagon), compactness(strip));
+        assert!(hexagon_score < 2.0 && strip_score > 4.0, "{} vs {}", hexagon_score, strip_score);
+    }
+}
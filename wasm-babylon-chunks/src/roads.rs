@@ -1,12 +1,15 @@
 /// Road network generation module
 
 use wasm_bindgen::prelude::*;
-use std::collections::HashSet;
-use crate::astar::hex_astar;
-use crate::hex_utils::{parse_valid_terrain_json, parse_path_json, hex_distance};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::astar::{hex_astar_route, hex_bfs_distances, weighted_astar_path};
+use crate::hex_utils::{parse_coords, parse_valid_terrain_json, parse_path_json, parse_cost_json, get_hex_neighbors, hex_distance, connected_components, CUBE_DIRECTIONS};
+use crate::utils::{coord_random, fmt_f64};
 
 /// Find nearest point in connected set to a given point
-/// Returns the nearest point and its distance
+/// Returns the nearest point and its distance; ties go to the smallest (q, r), so the
+/// result does not depend on the set's hash order
 fn find_nearest_in_set(
     point: (i32, i32),
     connected_set: &HashSet<(i32, i32)>,
@@ -20,7 +23,7 @@ fn find_nearest_in_set(
     
     for &connected_point in connected_set {
         let dist = hex_distance(point.0, point.1, connected_point.0, connected_point.1);
-        if dist < min_distance {
+        if dist < min_distance || (dist == min_distance && nearest.is_some_and(|n| connected_point < n)) {
             min_distance = dist;
             nearest = Some(connected_point);
         }
@@ -29,6 +32,46 @@ fn find_nearest_in_set(
     nearest.map(|n| (n, min_distance))
 }
 
+/// Parse seed points in input order, dropping repeats
+/// The first seed roots the tree, so `generate_road_network_with_loops` keeps the
+/// caller's order rather than hashing it
+fn parse_seed_list(seeds_json: &str) -> Vec<(i32, i32)> {
+    let mut seen = HashSet::new();
+    parse_coords(seeds_json)
+        .into_iter()
+        .filter(|seed| seen.insert(*seed))
+        .collect()
+}
+
+/// Growing-tree candidate edge: (key, unconnected point, connected road), where the key
+/// is the hex distance for the plain tree and the step cost for the weighted one
+type FrontierEdge = (i32, (i32, i32), (i32, i32));
+
+/// Growing-tree frontier: min-heap of candidate edges
+type RoadFrontier = BinaryHeap<Reverse<FrontierEdge>>;
+
+/// Road tiles and how many candidate edges were examined to grow them
+type CountedRoads = (HashSet<(i32, i32)>, usize);
+
+/// Push frontier edges from a road to each of its unconnected neighbors, keyed by
+/// `edge_key(neighbor)`
+/// Returns how many edges were pushed
+fn push_frontier_edges(
+    road: (i32, i32),
+    unconnected: &HashSet<(i32, i32)>,
+    frontier: &mut RoadFrontier,
+    edge_key: impl Fn((i32, i32)) -> i32,
+) -> usize {
+    let mut pushed = 0;
+    for neighbor in get_hex_neighbors(road.0, road.1) {
+        if unconnected.contains(&neighbor) {
+            frontier.push(Reverse((edge_key(neighbor), neighbor, road)));
+            pushed += 1;
+        }
+    }
+    pushed
+}
+
 /// Generate road network using true growing tree algorithm
 /// 
 /// Algorithm:
@@ -36,6 +79,10 @@ fn find_nearest_in_set(
 /// 2. For each remaining seed: find nearest connected road, build A* path, add path
 /// 3. For expansion: repeatedly find nearest unconnected valid terrain to any connected road,
 ///    build A* path, add path. Continue until target count reached.
+///    Candidates come from a frontier heap of (distance, unconnected, connected) edges that
+///    grows as tiles join the network, so each step is O(log n) instead of a full
+///    connected × unconnected rescan. Ties go to the smallest (q, r), which makes the
+///    expansion order deterministic.
 /// 
 /// This creates a true tree structure where every road is connected via a path,
 /// not just adjacent (which would be flood fill).
@@ -53,7 +100,7 @@ pub fn generate_road_network_growing_tree(
     target_count: i32,
 ) -> String {
     // Parse inputs
-    let seeds = parse_valid_terrain_json(&seeds_json);
+    let seeds: Vec<(i32, i32)> = parse_valid_terrain_json(&seeds_json).into_iter().collect();
     let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
     let occupied = parse_valid_terrain_json(&occupied_json);
     
@@ -65,15 +112,35 @@ pub fn generate_road_network_growing_tree(
         }
     }
     
-    // Convert valid terrain to JSON for hex_astar calls
-    let mut valid_terrain_vec: Vec<(i32, i32)> = valid_terrain_set.iter().cloned().collect();
-    valid_terrain_vec.sort();
-    let mut valid_terrain_json_parts = Vec::new();
-    for (q, r) in &valid_terrain_vec {
-        valid_terrain_json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    let connected = grow_road_network(&seeds, &valid_terrain_set, target_count);
+    
+    // Convert connected set to JSON array
+    let mut road_vec: Vec<(i32, i32)> = connected.iter().cloned().collect();
+    road_vec.sort();
+    let mut json_parts = Vec::new();
+    for (q, r) in road_vec {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
     }
-    let valid_terrain_json_for_astar = format!("[{}]", valid_terrain_json_parts.join(","));
     
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Growing-tree road network over an already filtered walkable set
+/// Seeds are connected in order (the first one roots the tree); returns the road tiles
+pub fn grow_road_network(
+    seeds: &[(i32, i32)],
+    valid_terrain_set: &HashSet<(i32, i32)>,
+    target_count: i32,
+) -> HashSet<(i32, i32)> {
+    grow_road_network_counted(seeds, valid_terrain_set, target_count).0
+}
+
+/// `grow_road_network` that also returns how many frontier edges it pushed
+fn grow_road_network_counted(
+    seeds: &[(i32, i32)],
+    valid_terrain_set: &HashSet<(i32, i32)>,
+    target_count: i32,
+) -> CountedRoads {
     // Connected set: roads in the network
     let mut connected: HashSet<(i32, i32)> = HashSet::new();
     
@@ -106,16 +173,15 @@ pub fn generate_road_network_growing_tree(
             // Find nearest connected road
             if let Some((nearest_road, _)) = find_nearest_in_set(*seed, &connected) {
                 // Build path from nearest road to seed
-                let path_json = hex_astar(
+                let path = hex_astar_route(
                     nearest_road.0,
                     nearest_road.1,
                     seed.0,
                     seed.1,
-                    valid_terrain_json_for_astar.clone(),
+                    valid_terrain_set,
                 );
                 
-                if path_json != "null" && !path_json.is_empty() {
-                    let path = parse_path_json(&path_json);
+                if let Some(path) = path {
                     // Add all path hexes to connected
                     for path_hex in path {
                         connected.insert(path_hex);
@@ -127,39 +193,48 @@ pub fn generate_road_network_growing_tree(
     }
     
     // Phase 2: Expand to target density using growing tree
-    while (connected.len() as i32) < target_count && !unconnected.is_empty() {
-        let mut best_unconnected: Option<(i32, i32)> = None;
-        let mut best_connected: Option<(i32, i32)> = None;
-        let mut min_distance = i32::MAX;
-        
-        // Find nearest unconnected point to any connected road
-        for &unconnected_point in &unconnected {
-            if let Some((nearest_road, distance)) = find_nearest_in_set(unconnected_point, &connected) {
-                if distance < min_distance {
-                    min_distance = distance;
-                    best_unconnected = Some(unconnected_point);
-                    best_connected = Some(nearest_road);
-                }
+    // Frontier of candidate (distance, unconnected, connected) edges. Every tile that
+    // joins the network pushes edges to its unconnected neighbors, so the nearest
+    // unconnected tile is popped from the heap instead of rescanning both sets.
+    let mut frontier: RoadFrontier = BinaryHeap::new();
+    let mut edges_pushed = 0;
+    for &road in &connected {
+        edges_pushed += push_frontier_edges(road, &unconnected, &mut frontier, |_| 1);
+    }
+    
+    while (connected.len() as i32) < target_count && !unconnected.is_empty() && !connected.is_empty() {
+        // Nearest unconnected point to any connected road; stale edges (target already
+        // connected) are skipped. Paths only cross walkable tiles, so the network is one
+        // walkable component: once no unconnected tile borders it, every tile left lies
+        // in another component and no A* path could reach it.
+        let mut best: Option<((i32, i32), (i32, i32))> = None;
+        while let Some(Reverse((_, unconnected_point, connected_road))) = frontier.pop() {
+            if unconnected.contains(&unconnected_point) {
+                best = Some((unconnected_point, connected_road));
+                break;
             }
         }
         
         // Build path and add to network
-        if let (Some(unconnected_point), Some(connected_road)) = (best_unconnected, best_connected) {
-            let path_json = hex_astar(
+        if let Some((unconnected_point, connected_road)) = best {
+            let path = hex_astar_route(
                 connected_road.0,
                 connected_road.1,
                 unconnected_point.0,
                 unconnected_point.1,
-                valid_terrain_json_for_astar.clone(),
+                valid_terrain_set,
             );
             
-            if path_json != "null" && !path_json.is_empty() {
-                let path = parse_path_json(&path_json);
+            if let Some(path) = path {
                 // Add all path hexes to connected
-                for path_hex in path {
+                for &path_hex in &path {
                     connected.insert(path_hex);
                     unconnected.remove(&path_hex);
                 }
+                // Extend the frontier once the whole path has joined
+                for &path_hex in &path {
+                    edges_pushed += push_frontier_edges(path_hex, &unconnected, &mut frontier, |_| 1);
+                }
             } else {
                 // Can't reach this point, remove it from unconnected
                 unconnected.remove(&unconnected_point);
@@ -170,8 +245,336 @@ pub fn generate_road_network_growing_tree(
         }
     }
     
+    (connected, edges_pushed)
+}
+
+
+/// Largest hex distance between two roads that a loop connector may bridge
+const MAX_LOOP_SPAN: i32 = 3;
+
+/// Generate a growing-tree road network with extra connectors that close loops
+/// 
+/// **Learning Point**: A tree has exactly one route between any two roads, so traffic
+/// is forced through its junctions and the map reads like a river delta. After growing
+/// the same tree as `generate_road_network_growing_tree`, this looks at pairs of roads
+/// that are close on the map (2 to `MAX_LOOP_SPAN` hexes apart) but far apart along the
+/// network (more than twice their hex distance), i.e. where a short connector would
+/// remove a long detour. Each such pair gets an A* connector with probability
+/// `loop_probability`, unless obstacles make the connector itself a detour. Pairs are
+/// checked against the network as it grows, so one new connector stops its neighbors
+/// from adding redundant parallel ones.
+/// 
+/// Seeds are taken in input order, so the first one roots the tree. Rolls come from
+/// `coord_random` with `seed`, so the same tree always gets the same loops; pass a seed
+/// drawn from the JS RNG to vary them per map. A probability of 0 returns just the tree.
+/// 
+/// @param seeds_json - JSON array of seed points: [{"q":0,"r":0},...]
+/// @param valid_terrain_json - JSON array of valid terrain: [{"q":0,"r":0},...]
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @param target_count - Target number of roads for the tree phase
+/// @param loop_probability - Chance (0-1) that each qualifying pair gets a connector
+/// @param seed - Seed for the connector rolls
+/// @returns JSON array of road coordinates sorted by (q, r): [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn generate_road_network_with_loops(
+    seeds_json: String,
+    valid_terrain_json: String,
+    occupied_json: String,
+    target_count: i32,
+    loop_probability: f64,
+    seed: u32,
+) -> String {
+    let seeds = parse_seed_list(&seeds_json);
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    let walkable: HashSet<(i32, i32)> = parse_valid_terrain_json(&valid_terrain_json)
+        .into_iter()
+        .filter(|hex| !occupied.contains(hex))
+        .collect();
+    
+    let mut roads = grow_road_network(&seeds, &walkable, target_count);
+    
+    if loop_probability > 0.0 {
+        // Candidate pairs from the tree, each listed once, in a fixed order
+        let mut tree_roads: Vec<(i32, i32)> = roads.iter().copied().collect();
+        tree_roads.sort();
+        let mut candidates = Vec::new();
+        for (index, &a) in tree_roads.iter().enumerate() {
+            for &b in &tree_roads[index + 1..] {
+                let distance = hex_distance(a.0, a.1, b.0, b.1);
+                if (2..=MAX_LOOP_SPAN).contains(&distance) {
+                    candidates.push((a, b, distance));
+                }
+            }
+        }
+        
+        for (index, (a, b, distance)) in candidates.into_iter().enumerate() {
+            if coord_random(seed, index as i32, 0) >= loop_probability {
+                continue;
+            }
+            if network_distance_within(&roads, a, b, 2 * distance) {
+                continue;
+            }
+            // A connector that has to detour around obstacles is no shortcut
+            if let Some(path) = hex_astar_route(a.0, a.1, b.0, b.1, &walkable) {
+                if path.len() as i32 - 1 <= 2 * distance {
+                    roads.extend(path);
+                }
+            }
+        }
+    }
+    
+    let mut road_vec: Vec<(i32, i32)> = roads.into_iter().collect();
+    road_vec.sort();
+    let mut json_parts = Vec::new();
+    for (q, r) in road_vec {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Whether `to` is reachable from `from` along roads in at most `max_steps` steps
+/// Breadth-first search cut off at `max_steps`, so it stays local to the pair
+fn network_distance_within(
+    roads: &HashSet<(i32, i32)>,
+    from: (i32, i32),
+    to: (i32, i32),
+    max_steps: i32,
+) -> bool {
+    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    visited.insert(from);
+    let mut frontier = vec![from];
+    
+    for _ in 0..max_steps {
+        let mut next_frontier = Vec::new();
+        for (q, r) in frontier {
+            for neighbor in get_hex_neighbors(q, r) {
+                if neighbor == to {
+                    return true;
+                }
+                if roads.contains(&neighbor) && visited.insert(neighbor) {
+                    next_frontier.push(neighbor);
+                }
+            }
+        }
+        if next_frontier.is_empty() {
+            break;
+        }
+        frontier = next_frontier;
+    }
+    
+    false
+}
+
+/// Generate a road network that connects the seeds with a minimum spanning tree
+/// 
+/// **Learning Point**: The growing tree attaches whichever tile is nearest at each step,
+/// which is greedy rather than minimal across the seeds. This builds a true minimum
+/// spanning tree over the seed points instead:
+/// 1. One BFS per seed over the walkable tiles gives the shortest path length between
+///    every pair of seeds (the same length A* would find, at one search per seed
+///    instead of one per pair)
+/// 2. Prim's algorithm picks the cheapest set of seed-to-seed links that connects them
+/// 3. Each chosen link is laid down as road tiles along its A* path
+/// 
+/// Only the seeds are connected - there is no expansion phase - so the network is the
+/// leanest one joining them. Seeds that cannot reach each other end up in separate
+/// trees, and seeds that are occupied or not valid terrain are ignored.
+/// 
+/// @param seeds_json - JSON array of seed points: [{"q":0,"r":0},...]
+/// @param valid_terrain_json - JSON array of valid terrain: [{"q":0,"r":0},...]
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @returns JSON array of road coordinates sorted by (q, r): [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn generate_road_network_mst(
+    seeds_json: String,
+    valid_terrain_json: String,
+    occupied_json: String,
+) -> String {
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    let walkable: HashSet<(i32, i32)> = parse_valid_terrain_json(&valid_terrain_json)
+        .into_iter()
+        .filter(|hex| !occupied.contains(hex))
+        .collect();
+    
+    // Usable seeds in input order, without duplicates
+    let mut seeds: Vec<(i32, i32)> = Vec::new();
+    for seed in parse_coords(&seeds_json) {
+        if walkable.contains(&seed) && !seeds.contains(&seed) {
+            seeds.push(seed);
+        }
+    }
+    
+    // Path length from each seed to every walkable tile it can reach
+    let distances: Vec<HashMap<(i32, i32), i32>> = seeds.iter()
+        .map(|&(q, r)| hex_bfs_distances(q, r, &walkable))
+        .collect();
+    
+    // Prim's algorithm; restarting from the first unreached seed turns it into a
+    // spanning forest when some seeds can't reach each other
+    let mut in_tree = vec![false; seeds.len()];
+    let mut best_link: Vec<Option<(i32, usize)>> = vec![None; seeds.len()];
+    let mut roads: HashSet<(i32, i32)> = HashSet::new();
+    
+    for _ in 0..seeds.len() {
+        // Cheapest seed to add next: linked seeds by link cost, else a new tree root
+        let next = (0..seeds.len())
+            .filter(|&index| !in_tree[index])
+            .min_by_key(|&index| match best_link[index] {
+                Some((cost, _)) => (0, cost, index),
+                None => (1, 0, index),
+            });
+        let next = match next {
+            Some(next) => next,
+            None => break,
+        };
+        in_tree[next] = true;
+        
+        let (seed_q, seed_r) = seeds[next];
+        match best_link[next] {
+            Some((_, parent)) => {
+                let (parent_q, parent_r) = seeds[parent];
+                if let Some(path) = hex_astar_route(parent_q, parent_r, seed_q, seed_r, &walkable) {
+                    roads.extend(path);
+                }
+            }
+            None => {
+                roads.insert((seed_q, seed_r));
+            }
+        }
+        
+        // Offer cheaper links through the seed that just joined
+        for index in 0..seeds.len() {
+            if in_tree[index] {
+                continue;
+            }
+            if let Some(&cost) = distances[next].get(&seeds[index]) {
+                if best_link[index].is_none_or(|(best, _)| cost < best) {
+                    best_link[index] = Some((cost, next));
+                }
+            }
+        }
+    }
+    
+    let mut road_vec: Vec<(i32, i32)> = roads.into_iter().collect();
+    road_vec.sort();
+    let mut json_parts = Vec::new();
+    for (q, r) in road_vec {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Generate road network with a growing tree that follows terrain costs
+/// 
+/// Same two phases as `generate_road_network_growing_tree`, but terrain difficulty
+/// steers the growth so roads hug valleys and plains:
+/// 1. Seeds are connected to the nearest connected road with weighted A*, so the
+///    links route around expensive terrain instead of straight through it
+/// 2. Expansion uses the growing tree's frontier heap, with each edge keyed by the cost
+///    of its unconnected tile instead of its distance, so the cheapest tile next to the
+///    network is connected next, along a weighted A* path. Tiles join the frontier as
+///    their neighbors join the network, so nothing is rescanned.
+/// 
+/// Valid terrain tiles missing from `cost_json` (or with a cost below 1) cost 1.
+/// 
+/// @param seeds_json - JSON array of seed points: [{"q":0,"r":0},...]
+/// @param valid_terrain_json - JSON array of valid terrain: [{"q":0,"r":0},...]
+/// @param cost_json - JSON array of per-tile costs: [{"q":0,"r":0,"cost":3},...]
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @param target_count - Target number of roads to generate
+/// @returns JSON array of road coordinates: [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn generate_road_network_weighted(
+    seeds_json: String,
+    valid_terrain_json: String,
+    cost_json: String,
+    occupied_json: String,
+    target_count: i32,
+) -> String {
+    // Parse inputs (seeds keep their input order)
+    let seeds = parse_path_json(&seeds_json);
+    let valid_terrain = parse_valid_terrain_json(&valid_terrain_json);
+    let tile_costs = parse_cost_json(&cost_json);
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    
+    // Cost map over valid terrain minus occupied - doubles as the walkable set for A*
+    let mut costs: HashMap<(i32, i32), i32> = HashMap::new();
+    for &hex in &valid_terrain {
+        if !occupied.contains(&hex) {
+            costs.insert(hex, tile_costs.get(&hex).copied().unwrap_or(1));
+        }
+    }
+    
+    let mut connected: HashSet<(i32, i32)> = HashSet::new();
+    
+    // Phase 1: Connect seed points along the cheapest routes
+    for seed in seeds {
+        if !costs.contains_key(&seed) || connected.contains(&seed) {
+            continue;
+        }
+        
+        if connected.is_empty() {
+            connected.insert(seed);
+            continue;
+        }
+        
+        if let Some((nearest_road, _)) = find_nearest_in_set(seed, &connected) {
+            if let Some((path, _)) = weighted_astar_path(nearest_road.0, nearest_road.1, seed.0, seed.1, &costs) {
+                connected.extend(path);
+            }
+        }
+    }
+    
+    // Phase 2: Expand through the cheapest frontier tile first
+    // Same frontier heap as the growing tree, but each edge is keyed by the cost of
+    // stepping onto its unconnected tile instead of by distance
+    let mut unconnected: HashSet<(i32, i32)> = costs.keys()
+        .filter(|hex| !connected.contains(hex))
+        .copied()
+        .collect();
+    let step_cost = |tile: (i32, i32)| costs[&tile];
+    let mut frontier: RoadFrontier = BinaryHeap::new();
+    for &road in &connected {
+        push_frontier_edges(road, &unconnected, &mut frontier, step_cost);
+    }
+    
+    while (connected.len() as i32) < target_count {
+        // Cheapest unconnected tile next to the network; stale edges (target already
+        // connected) are skipped
+        let mut best: Option<((i32, i32), (i32, i32))> = None;
+        while let Some(Reverse((_, unconnected_point, connected_road))) = frontier.pop() {
+            if unconnected.contains(&unconnected_point) {
+                best = Some((unconnected_point, connected_road));
+                break;
+            }
+        }
+        let (unconnected_point, connected_road) = match best {
+            Some(edge) => edge,
+            None => break, // No more reachable tiles
+        };
+        
+        // Build the weighted path and add it to the network
+        match weighted_astar_path(connected_road.0, connected_road.1, unconnected_point.0, unconnected_point.1, &costs) {
+            Some((path, _)) => {
+                for &path_hex in &path {
+                    connected.insert(path_hex);
+                    unconnected.remove(&path_hex);
+                }
+                // Extend the frontier once the whole path has joined
+                for &path_hex in &path {
+                    push_frontier_edges(path_hex, &unconnected, &mut frontier, step_cost);
+                }
+            }
+            None => {
+                unconnected.remove(&unconnected_point);
+            }
+        }
+    }
+    
     // Convert connected set to JSON array
-    let mut road_vec: Vec<(i32, i32)> = connected.iter().cloned().collect();
+    let mut road_vec: Vec<(i32, i32)> = connected.into_iter().collect();
     road_vec.sort();
     let mut json_parts = Vec::new();
     for (q, r) in road_vec {
@@ -181,3 +584,235 @@ pub fn generate_road_network_growing_tree(
     format!("[{}]", json_parts.join(","))
 }
 
+/// Find water tiles where a road would continue straight across (bridge sites)
+/// 
+/// **Learning Point**: `CUBE_DIRECTIONS[d]` and `CUBE_DIRECTIONS[d + 3]` point in
+/// opposite directions, so checking the three pairs (0,3), (1,4), (2,5) finds every
+/// water tile with road on two opposite sides. A bridge there keeps the road straight,
+/// which reads far better than one that bends on the water.
+/// 
+/// @param roads_json - JSON array of road hexes: [{"q":0,"r":0},...]
+/// @param water_json - JSON array of water hexes: [{"q":0,"r":0},...]
+/// @returns JSON array of bridge sites sorted by (q, r): [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn find_bridge_sites(roads_json: String, water_json: String) -> String {
+    let roads = parse_valid_terrain_json(&roads_json);
+    let water = parse_valid_terrain_json(&water_json);
+    
+    let mut sites: Vec<(i32, i32)> = water.into_iter()
+        .filter(|&(q, r)| {
+            (0..3).any(|direction| {
+                let forward = CUBE_DIRECTIONS[direction];
+                let backward = CUBE_DIRECTIONS[direction + 3];
+                roads.contains(&(q + forward.q, r + forward.r))
+                    && roads.contains(&(q + backward.q, r + backward.r))
+            })
+        })
+        .collect();
+    sites.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in sites {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Count each road's neighboring road tiles, sorted by (q, r)
+fn road_degrees(roads: &HashSet<(i32, i32)>) -> Vec<((i32, i32), usize)> {
+    let mut degrees: Vec<((i32, i32), usize)> = roads.iter()
+        .map(|&(q, r)| {
+            let degree = get_hex_neighbors(q, r)
+                .into_iter()
+                .filter(|neighbor| roads.contains(neighbor))
+                .count();
+            ((q, r), degree)
+        })
+        .collect();
+    degrees.sort();
+    degrees
+}
+
+/// Classify a road tile by its number of road neighbors
+fn road_kind(degree: usize) -> &'static str {
+    match degree {
+        0 => "isolated",
+        1 => "dead_end",
+        2 => "straight",
+        _ => "junction",
+    }
+}
+
+/// Classify every road tile as a dead end, straight section or junction
+/// 
+/// **Learning Point**: A road tile's degree is how many of its six neighbors are also
+/// roads. Degree 1 is a dead end (cul-de-sac), 2 is a plain section of road (straight
+/// or bending), and 3 or more is a junction (signpost). A road with no road neighbors
+/// is reported too, as "isolated", since it usually means a broken network.
+/// 
+/// @param roads_json - JSON array of road hexes: [{"q":0,"r":0},...]
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"degree":3,"kind":"junction"},...]
+///          where kind is "isolated", "dead_end", "straight" or "junction"
+#[wasm_bindgen]
+pub fn road_topology_json(roads_json: String) -> String {
+    let roads = parse_valid_terrain_json(&roads_json);
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), degree) in road_degrees(&roads) {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"degree":{},"kind":"{}"}}"#,
+            q, r, degree, road_kind(degree)
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Summarize a road network in one call
+/// 
+/// **Learning Point**: A quick quality readout for a generated network, computed from
+/// one parse of the roads. Degrees come from the same neighbor count as
+/// `road_topology_json`, and `components` uses the same BFS as `road_components_json`,
+/// so the numbers always agree with those functions on the same input. A fully
+/// connected network has `components` 1; `avg_degree` is 0 for an empty network.
+/// 
+/// @param roads_json - JSON array of road hexes: [{"q":0,"r":0},...]
+/// @returns JSON object {"count":40,"components":1,"dead_ends":6,"junctions":4,"avg_degree":2.1}
+#[wasm_bindgen]
+pub fn road_network_metrics(roads_json: String) -> String {
+    let roads = parse_valid_terrain_json(&roads_json);
+    let degrees = road_degrees(&roads);
+    
+    let count = degrees.len();
+    let dead_ends = degrees.iter().filter(|(_, degree)| *degree == 1).count();
+    let junctions = degrees.iter().filter(|(_, degree)| *degree >= 3).count();
+    let degree_sum: usize = degrees.iter().map(|(_, degree)| degree).sum();
+    let avg_degree = if count == 0 { 0.0 } else { degree_sum as f64 / count as f64 };
+    let components = connected_components(&roads).len();
+    
+    format!(
+        r#"{{"count":{},"components":{},"dead_ends":{},"junctions":{},"avg_degree":{}}}"#,
+        count, components, dead_ends, junctions, fmt_f64(avg_degree)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{coords_json, disk};
+    use crate::hex_utils::generate_hex_grid;
+    
+    /// The pre-frontier growing tree: every step scans all (unconnected, connected) pairs
+    /// for the nearest one, ties to the smallest (distance, unconnected, connected).
+    /// Returns the roads and how many pair distances it evaluated.
+    fn grow_by_full_scan(
+        seeds: &[(i32, i32)],
+        valid_terrain_set: &HashSet<(i32, i32)>,
+        target_count: i32,
+    ) -> CountedRoads {
+        let mut connected: HashSet<(i32, i32)> = HashSet::new();
+        let mut unconnected = valid_terrain_set.clone();
+        for &seed in seeds {
+            if !valid_terrain_set.contains(&seed) {
+                continue;
+            }
+            match find_nearest_in_set(seed, &connected) {
+                None => {
+                    connected.insert(seed);
+                    unconnected.remove(&seed);
+                },
+                Some((nearest_road, _)) => {
+                    for hex in hex_astar_route(nearest_road.0, nearest_road.1, seed.0, seed.1, valid_terrain_set).unwrap_or_default() {
+                        connected.insert(hex);
+                        unconnected.remove(&hex);
+                    }
+                },
+            }
+        }
+        
+        let mut evaluated = 0;
+        while (connected.len() as i32) < target_count && !unconnected.is_empty() && !connected.is_empty() {
+            let mut best: Option<FrontierEdge> = None;
+            for &point in &unconnected {
+                evaluated += connected.len();
+                let (road, distance) = find_nearest_in_set(point, &connected).unwrap();
+                if best.is_none_or(|current| (distance, point, road) < current) {
+                    best = Some((distance, point, road));
+                }
+            }
+            let (_, point, road) = best.unwrap();
+            match hex_astar_route(road.0, road.1, point.0, point.1, valid_terrain_set) {
+                Some(path) => for hex in path {
+                    connected.insert(hex);
+                    unconnected.remove(&hex);
+                },
+                None => {
+                    unconnected.remove(&point);
+                },
+            }
+        }
+        (connected, evaluated)
+    }
+    
+    #[test]
+    fn frontier_matches_full_scan_on_small_inputs() {
+        // The layer-5 ring is a moat, so layers 6 and 7 are an unreachable island
+        let terrain: HashSet<(i32, i32)> = disk(7).into_iter().filter(|&(q, r)| hex_distance(0, 0, q, r) != 5).collect();
+        let seeds = [(0, 0), (3, -1), (-2, 4), (6, 0)];
+        
+        for target in [0, 1, 5, 20, 61, 1000] {
+            let (roads, _) = grow_road_network_counted(&seeds, &terrain, target);
+            assert_eq!(roads, grow_by_full_scan(&seeds, &terrain, target).0, "target {}", target);
+        }
+    }
+    
+    #[test]
+    fn frontier_benchmark_30_layer_grid() {
+        let grid = generate_hex_grid(30, 0, 0);
+        let terrain: HashSet<(i32, i32)> = grid.iter().map(|hex| (hex.q, hex.r)).collect();
+        let seeds = [(0, 0), (12, -5), (-20, 8)];
+        
+        // The full scan is cubic, so it is only run to a modest target
+        let (roads, frontier_nodes) = grow_road_network_counted(&seeds, &terrain, 150);
+        let (scan_roads, scan_nodes) = grow_by_full_scan(&seeds, &terrain, 150);
+        assert_eq!(roads, scan_roads);
+        assert!(scan_nodes >= 10 * frontier_nodes, "scan {} vs frontier {}", scan_nodes, frontier_nodes);
+        
+        // Growing over the whole grid stays linear: at most six edges per road
+        let (roads, frontier_nodes) = grow_road_network_counted(&seeds, &terrain, grid.len() as i32);
+        assert_eq!(roads.len(), grid.len());
+        assert!(frontier_nodes <= 6 * grid.len(), "frontier {}", frontier_nodes);
+    }
+    
+    #[test]
+    fn weighted_network_routes_around_expensive_region() {
+        let terrain: Vec<(i32, i32)> = disk(4);
+        // A costly wall across the middle with a cheap gap at its southern end
+        let wall: Vec<(i32, i32)> = (-4..=1).map(|r| (0, r)).collect();
+        let cost_parts: Vec<String> = wall.iter()
+            .map(|(q, r)| format!(r#"{{"q":{},"r":{},"cost":50}}"#, q, r))
+            .collect();
+        let cost_json = format!("[{}]", cost_parts.join(","));
+        
+        let roads = parse_coords(&generate_road_network_weighted(
+            coords_json(&[(-3, 0), (3, 0)]),
+            coords_json(&terrain),
+            cost_json,
+            "[]".to_string(),
+            0,
+        ));
+        
+        assert!(roads.contains(&(-3, 0)) && roads.contains(&(3, 0)));
+        assert!(roads.iter().all(|hex| !wall.contains(hex)), "{:?}", roads);
+        assert!(roads.iter().any(|&(q, r)| q == 0 && r >= 2));
+    }
+    
+    #[test]
+    fn water_gap_in_road_is_a_bridge_site() {
+        // A road along r = 0 broken by water at (0,0), with more water off to the side
+        let roads = coords_json(&[(-2, 0), (-1, 0), (1, 0), (2, 0)]);
+        let water = coords_json(&[(0, 0), (0, 1), (-1, 1)]);
+        assert_eq!(find_bridge_sites(roads, water), r#"[{"q":0,"r":0}]"#);
+    }
+}
@@ -1,6 +1,6 @@
 /// WFC state management module
 
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, MutexGuard};
 use std::collections::HashMap;
 use crate::types::TileType;
 
@@ -11,6 +11,15 @@ use crate::types::TileType;
 pub struct WfcState {
     grid: HashMap<(i32, i32), TileType>,
     pre_constraints: HashMap<(i32, i32), TileType>,
+    /// Grid as of the last `take_grid_patch` call (what the renderer has)
+    synced_grid: HashMap<(i32, i32), TileType>,
+}
+
+/// Difference between two grid snapshots, each list sorted by (q, r)
+pub struct GridPatch {
+    pub added: Vec<((i32, i32), TileType)>,
+    pub changed: Vec<((i32, i32), TileType)>,
+    pub removed: Vec<(i32, i32)>,
 }
 
 impl WfcState {
@@ -18,6 +27,7 @@ impl WfcState {
         WfcState {
             grid: HashMap::new(),
             pre_constraints: HashMap::new(),
+            synced_grid: HashMap::new(),
         }
     }
     
@@ -53,12 +63,58 @@ impl WfcState {
         self.grid.insert((q, r), tile_type);
     }
     
+    /// Remove tile from grid, returning the previous tile type if any
+    pub fn remove_tile(&mut self, q: i32, r: i32) -> Option<TileType> {
+        self.grid.remove(&(q, r))
+    }
+    
     /// Get grid values iterator
     pub fn grid_values(&self) -> impl Iterator<Item = TileType> + '_ {
         self.grid.values().copied()
     }
+    
+    /// Diff the grid against the last synced snapshot, then make the grid the new snapshot
+    pub fn take_grid_patch(&mut self) -> GridPatch {
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        for (key, tile_type) in &self.grid {
+            match self.synced_grid.get(key) {
+                None => added.push((*key, *tile_type)),
+                Some(old) if old != tile_type => changed.push((*key, *tile_type)),
+                Some(_) => {},
+            }
+        }
+        
+        let mut removed: Vec<(i32, i32)> = self.synced_grid.keys()
+            .filter(|key| !self.grid.contains_key(key))
+            .copied()
+            .collect();
+        
+        added.sort_by_key(|(key, _)| *key);
+        changed.sort_by_key(|(key, _)| *key);
+        removed.sort();
+        
+        self.synced_grid = self.grid.clone();
+        GridPatch { added, changed, removed }
+    }
+    
+    /// Get grid entries iterator
+    pub fn grid_tiles(&self) -> impl Iterator<Item = ((i32, i32), TileType)> + '_ {
+        self.grid.iter().map(|((q, r), tile_type)| ((*q, *r), *tile_type))
+    }
 }
 
 /// Global WFC state (thread-safe)
 pub static WFC_STATE: LazyLock<Mutex<WfcState>> = LazyLock::new(|| Mutex::new(WfcState::new()));
 
+/// Held by tests that go through `WFC_STATE`, so parallel tests do not see each other's grids
+#[cfg(test)]
+pub static STATE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Lock a state mutex, recovering it if a previous call panicked while holding it
+/// A poisoned lock would otherwise make every later call panic until the module reloads;
+/// the state is used as the panicking call left it
+pub fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
@@ -0,0 +1,271 @@
+//! Terrain analysis module
+//!
+//! Functions here analyze the walkable graph formed by a set of valid terrain
+//! hexes, where two tiles are connected when they are hex neighbors.
+
+use wasm_bindgen::prelude::*;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::astar::hex_bfs_distances;
+use crate::hex_utils::{parse_valid_terrain_json, get_hex_neighbors, axial_to_cube, cube_ring, hex_distance};
+
+/// Get the walkable neighbors of a tile
+fn walkable_neighbors(q: i32, r: i32, walkable: &HashSet<(i32, i32)>) -> Vec<(i32, i32)> {
+    get_hex_neighbors(q, r)
+        .into_iter()
+        .filter(|neighbor| walkable.contains(neighbor))
+        .collect()
+}
+
+/// Collect every walkable tile with exactly one walkable neighbor, sorted by (q, r)
+fn collect_dead_ends(walkable: &HashSet<(i32, i32)>) -> Vec<(i32, i32)> {
+    let mut dead_ends: Vec<(i32, i32)> = walkable.iter()
+        .filter(|&&(q, r)| walkable_neighbors(q, r, walkable).len() == 1)
+        .copied()
+        .collect();
+    dead_ends.sort();
+    dead_ends
+}
+
+/// Find dead-end tiles in arbitrary terrain
+/// 
+/// **Learning Point**: A dead end is a walkable tile with exactly one walkable
+/// neighbor - the tip of a cul-de-sac. Unlike road classification this works on
+/// any terrain set, which makes it useful for placing treasure or secrets.
+/// 
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @returns JSON array of dead-end hexes sorted by (q, r): [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn find_dead_ends(valid_terrain_json: String) -> String {
+    let walkable = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in collect_dead_ends(&walkable) {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Find whole single-width dead-end corridors in arbitrary terrain
+/// 
+/// **Learning Point**: From each dead end, walk through tiles with exactly two walkable
+/// neighbors until reaching a branch (3+ neighbors). The branch tile is not part of the
+/// corridor. A corridor that is a dead end at both ends (an isolated strip) is reported
+/// once, starting from its smaller (q, r) end.
+/// 
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @returns JSON array of corridors, each ordered from the dead end inward:
+///          [[{"q":0,"r":0},{"q":1,"r":0}],...]
+#[wasm_bindgen]
+pub fn find_dead_end_corridors(valid_terrain_json: String) -> String {
+    let walkable = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let mut corridors: Vec<Vec<(i32, i32)>> = Vec::new();
+    for dead_end in collect_dead_ends(&walkable) {
+        let mut corridor = vec![dead_end];
+        let mut previous = dead_end;
+        let mut current = walkable_neighbors(dead_end.0, dead_end.1, &walkable)[0];
+        
+        loop {
+            let neighbors = walkable_neighbors(current.0, current.1, &walkable);
+            match neighbors.len() {
+                // Reached the other end of an isolated strip
+                1 => {
+                    corridor.push(current);
+                    break;
+                },
+                // Still inside the corridor - keep walking away from where we came from
+                2 => {
+                    corridor.push(current);
+                    let next = if neighbors[0] == previous { neighbors[1] } else { neighbors[0] };
+                    previous = current;
+                    current = next;
+                },
+                // Branch reached - corridor ends before it
+                _ => break,
+            }
+        }
+        
+        // Isolated strips are found from both ends - keep the walk from the smaller end
+        let last = corridor[corridor.len() - 1];
+        if corridor.len() > 1 && walkable_neighbors(last.0, last.1, &walkable).len() == 1 && last < dead_end {
+            continue;
+        }
+        
+        corridors.push(corridor);
+    }
+    
+    let mut corridor_parts = Vec::new();
+    for corridor in corridors {
+        let mut json_parts = Vec::new();
+        for (q, r) in corridor {
+            json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+        }
+        corridor_parts.push(format!("[{}]", json_parts.join(",")));
+    }
+    
+    format!("[{}]", corridor_parts.join(","))
+}
+
+/// Compute each tile's distance to the edge of the walkable area
+/// 
+/// **Learning Point**: Multi-source BFS. Every tile with at least one missing
+/// (non-walkable) neighbor is an edge tile and seeds the search at distance 0; the
+/// BFS then spreads inward one ring per step. The result is an inverse distance field:
+/// edges are 0, and values grow toward the interior, which is handy for shoreline
+/// gradients and edge falloff effects.
+/// 
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"dist":2},...]
+#[wasm_bindgen]
+pub fn distance_to_boundary(valid_terrain_json: String) -> String {
+    let walkable = parse_valid_terrain_json(&valid_terrain_json);
+    
+    let mut distances: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut queue: VecDeque<(i32, i32)> = VecDeque::new();
+    
+    // Seed with edge tiles
+    for &(q, r) in &walkable {
+        if walkable_neighbors(q, r, &walkable).len() < 6 {
+            distances.insert((q, r), 0);
+            queue.push_back((q, r));
+        }
+    }
+    
+    while let Some((q, r)) = queue.pop_front() {
+        let next_distance = distances[&(q, r)] + 1;
+        for neighbor in walkable_neighbors(q, r, &walkable) {
+            if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                entry.insert(next_distance);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    
+    let mut tiles: Vec<((i32, i32), i32)> = distances.into_iter().collect();
+    tiles.sort();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), dist) in tiles {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"dist":{}}}"#, q, r, dist));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Find the nearest free walkable tile to a target, for spawning units
+/// 
+/// **Learning Point**: Searches ring by ring outward from the target (radius 0 is the
+/// target itself), so the first ring with a free walkable tile holds the nearest ones by
+/// `hex_distance`, and a free target or neighbor is found after checking a handful of
+/// hexes. Among equally near tiles the smallest (q, r) wins, which keeps spawns
+/// deterministic. The search stops at the farthest walkable tile, so it always terminates.
+/// 
+/// @param q - Target q coordinate
+/// @param r - Target r coordinate
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @param occupied_json - JSON array of occupied hexes: [{"q":0,"r":0},...]
+/// @returns JSON object {"q":0,"r":0}, or "null" if every walkable tile is occupied
+#[wasm_bindgen]
+pub fn nearest_free_tile(q: i32, r: i32, valid_terrain_json: String, occupied_json: String) -> String {
+    let walkable = parse_valid_terrain_json(&valid_terrain_json);
+    let occupied = parse_valid_terrain_json(&occupied_json);
+    
+    let max_radius = walkable.iter()
+        .map(|&(wq, wr)| hex_distance(q, r, wq, wr))
+        .max()
+        .unwrap_or(-1);
+    
+    let center = axial_to_cube(q, r);
+    for radius in 0..=max_radius {
+        let nearest = cube_ring(center, radius)
+            .into_iter()
+            .map(|hex| (hex.q, hex.r))
+            .filter(|key| walkable.contains(key) && !occupied.contains(key))
+            .min();
+        if let Some((fq, fr)) = nearest {
+            return format!(r#"{{"q":{},"r":{}}}"#, fq, fr);
+        }
+    }
+    
+    "null".to_string()
+}
+
+/// Compute the fraction of walkable tiles reachable from a reference tile
+/// 
+/// **Learning Point**: A quick map-quality check - flood fill (BFS) from the start and
+/// divide the number of tiles reached by the number of walkable tiles. A ratio below 1
+/// means the map has pockets the player can never reach.
+/// 
+/// @param start_q - Reference q coordinate
+/// @param start_r - Reference r coordinate
+/// @param valid_terrain_json - JSON array of walkable hexes: [{"q":0,"r":0},...]
+/// @returns Ratio in [0, 1]; 0 if the start is not walkable, 1 if everything is reachable
+#[wasm_bindgen]
+pub fn accessibility_ratio(start_q: i32, start_r: i32, valid_terrain_json: String) -> f64 {
+    let walkable = parse_valid_terrain_json(&valid_terrain_json);
+    if !walkable.contains(&(start_q, start_r)) {
+        return 0.0;
+    }
+    
+    let reachable = hex_bfs_distances(start_q, start_r, &walkable).len();
+    reachable as f64 / walkable.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{coords_json, disk};
+    use crate::hex_utils::parse_int_fields_json;
+    
+    #[test]
+    fn nearest_free_tile_skips_occupied_first_ring() {
+        let mut occupied = vec![(0, 0)];
+        occupied.extend(get_hex_neighbors(0, 0));
+        let result = nearest_free_tile(0, 0, coords_json(&disk(3)), coords_json(&occupied));
+        // Smallest (q, r) among the 12 second-ring tiles
+        assert_eq!(result, r#"{"q":-2,"r":0}"#);
+        assert_eq!(hex_distance(0, 0, -2, 0), 2);
+    }
+    
+    #[test]
+    fn nearest_free_tile_returns_free_target() {
+        assert_eq!(nearest_free_tile(1, 1, coords_json(&disk(3)), "[]".to_string()), r#"{"q":1,"r":1}"#);
+    }
+    
+    #[test]
+    fn nearest_free_tile_handles_off_map_and_full_maps() {
+        let result = nearest_free_tile(40, 0, coords_json(&disk(2)), "[]".to_string());
+        assert_eq!(result, r#"{"q":2,"r":0}"#);
+        assert_eq!(nearest_free_tile(0, 0, coords_json(&disk(1)), coords_json(&disk(1))), "null");
+        assert_eq!(nearest_free_tile(0, 0, "[]".to_string(), "[]".to_string()), "null");
+    }
+    
+    #[test]
+    fn dead_end_branch_is_returned_in_order() {
+        // A main corridor from (-3,3) to (3,0) with a branch toward (0,-3) at (0,0)
+        let corridor = r#"[{"q":-3,"r":3},{"q":-2,"r":2},{"q":-1,"r":1},{"q":0,"r":0},{"q":1,"r":0},{"q":2,"r":0},{"q":3,"r":0},{"q":0,"r":-1},{"q":0,"r":-2},{"q":0,"r":-3}]"#;
+        let corridors = find_dead_end_corridors(corridor.to_string());
+        assert!(corridors.contains(r#"[{"q":0,"r":-3},{"q":0,"r":-2},{"q":0,"r":-1}]"#), "{}", corridors);
+        assert!(!corridors.contains(r#"{"q":0,"r":0}"#));
+    }
+    
+    #[test]
+    fn disk_center_is_farthest_from_boundary() {
+        let distances = parse_int_fields_json(&distance_to_boundary(coords_json(&disk(4))), &["q", "r", "dist"]);
+        let center = distances.iter().find(|fields| fields[0] == 0 && fields[1] == 0).unwrap()[2];
+        assert_eq!(center, 4);
+        assert!(distances.iter().all(|fields| fields[2] < center || (fields[0], fields[1]) == (0, 0)));
+    }
+    
+    #[test]
+    fn two_equal_components_give_half_accessibility() {
+        // Two radius-2 disks far apart
+        let mut terrain = disk(2);
+        terrain.extend(disk(2).into_iter().map(|(q, r)| (q + 10, r)));
+        let ratio = accessibility_ratio(0, 0, coords_json(&terrain));
+        assert!((ratio - 0.5).abs() < 1e-9, "{}", ratio);
+        assert_eq!(accessibility_ratio(5, 0, coords_json(&terrain)), 0.0);
+    }
+}
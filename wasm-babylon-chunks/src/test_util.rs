@@ -0,0 +1,16 @@
+//! Shared fixtures for unit tests
+
+use crate::hex_utils::generate_hex_grid;
+
+/// Serialize coordinates as a JSON array: [{"q":0,"r":0},...]
+pub(crate) fn coords_json(coords: &[(i32, i32)]) -> String {
+    let parts: Vec<String> = coords.iter()
+        .map(|(q, r)| format!(r#"{{"q":{},"r":{}}}"#, q, r))
+        .collect();
+    format!("[{}]", parts.join(","))
+}
+
+/// Every hex within `radius` steps of the origin
+pub(crate) fn disk(radius: i32) -> Vec<(i32, i32)> {
+    generate_hex_grid(radius, 0, 0).iter().map(|hex| (hex.q, hex.r)).collect()
+}
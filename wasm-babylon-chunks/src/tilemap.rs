@@ -0,0 +1,347 @@
+//! Tile map transformation module
+//!
+//! Functions here take a tile map as JSON (`[{"q":0,"r":0,"tileType":3},...]`)
+//! and return a new tile map in the same format, leaving `WFC_STATE` untouched.
+
+use wasm_bindgen::prelude::*;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::types::TileType;
+use crate::hex_utils::{parse_tile_map_json, tile_map_to_json, get_hex_neighbors};
+use crate::utils::coord_random;
+
+/// Blend two tile maps for dissolve-style transition animations
+/// 
+/// **Learning Point**: Each tile gets its own seeded roll in [0, 1) and flips from its
+/// `from` state to its `to` state when the roll is below `t`. Because the roll depends
+/// only on (seed, q, r), stepping `t` from 0 to 1 flips tiles progressively and never
+/// un-flips one, which gives a stable dissolve. Tiles present in only one map appear
+/// or disappear the same way.
+/// 
+/// @param from_json - Start tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @param to_json - End tile map: [{"q":0,"r":0,"tileType":4},...]
+/// @param t - Transition progress, clamped to [0, 1] (0 = from, 1 = to)
+/// @param seed - Seed for the per-tile rolls
+/// @returns JSON tile map sorted by (q, r): [{"q":0,"r":0,"tileType":N},...]
+#[wasm_bindgen]
+pub fn blend_tile_maps(from_json: String, to_json: String, t: f64, seed: u32) -> String {
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+    
+    let from: HashMap<(i32, i32), TileType> = parse_tile_map_json(&from_json).into_iter().collect();
+    let to: HashMap<(i32, i32), TileType> = parse_tile_map_json(&to_json).into_iter().collect();
+    
+    let keys: HashSet<(i32, i32)> = from.keys().chain(to.keys()).copied().collect();
+    
+    let mut blended: HashMap<(i32, i32), TileType> = HashMap::new();
+    for (q, r) in keys {
+        let from_tile = from.get(&(q, r)).copied();
+        let to_tile = to.get(&(q, r)).copied();
+        
+        let tile = if from_tile == to_tile || coord_random(seed, q, r) >= t {
+            from_tile
+        } else {
+            to_tile
+        };
+        
+        if let Some(tile_type) = tile {
+            blended.insert((q, r), tile_type);
+        }
+    }
+    
+    tile_map_to_json(&blended)
+}
+
+/// Spread the bits of a 32-bit value so they occupy the even bits of a u64
+fn spread_bits(value: u32) -> u64 {
+    let mut x = value as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    x = (x | (x << 1)) & 0x5555_5555_5555_5555;
+    x
+}
+
+/// Sort tiles along a Morton (Z-order) space-filling curve
+/// 
+/// **Learning Point**: Coordinates are first offset so the smallest q and r become 0,
+/// then the bits of q and r are interleaved into a single Morton code. Sorting by that
+/// code walks the map in nested Z-shaped blocks, so tiles that are close on the map
+/// usually end up close in the output array. That improves locality when the JS side
+/// builds instanced meshes in array order. Ties (duplicate coordinates) keep input order.
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @returns The same tiles in Morton order: [{"q":0,"r":0,"tileType":0},...]
+#[wasm_bindgen]
+pub fn grid_morton_order(tiles_json: String) -> String {
+    let mut tiles = parse_tile_map_json(&tiles_json);
+    
+    let min_q = tiles.iter().map(|((q, _), _)| *q).min().unwrap_or(0);
+    let min_r = tiles.iter().map(|((_, r), _)| *r).min().unwrap_or(0);
+    
+    // Offsets are non-negative and fit in u32 even for the full i32 range
+    tiles.sort_by_key(|((q, r), _)| {
+        let x = (*q as i64 - min_q as i64) as u32;
+        let y = (*r as i64 - min_r as i64) as u32;
+        spread_bits(x) | (spread_bits(y) << 1)
+    });
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in tiles {
+        json_parts.push(format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, tile_type as i32));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Convert land bordering water into a shore band
+/// 
+/// **Learning Point**: Sand is the natural beach, but the caller picks the shore type
+/// so other looks (e.g. a grass verge) work too. A multi-source BFS
+/// starts from every water tile at distance 0 and stops expanding at `width`, so each
+/// non-water tile within `width` hex steps of any water converts to `beach_type`.
+/// Distances are measured through any hex, so gaps in the map do not block the shore.
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @param beach_type - Tile type for the shore band (0-6)
+/// @param width - Shore width in hex steps (0 leaves the map unchanged)
+/// @returns JSON tile map sorted by (q, r): [{"q":0,"r":0,"tileType":N},...],
+///          or "null" if beach_type or width is invalid
+#[wasm_bindgen]
+pub fn erode_coastline(tiles_json: String, beach_type: i32, width: i32) -> String {
+    let beach_type = match TileType::from_i32(beach_type) {
+        Some(beach_type) => beach_type,
+        None => return "null".to_string(),
+    };
+    if width < 0 {
+        return "null".to_string();
+    }
+    
+    let mut tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&tiles_json).into_iter().collect();
+    
+    let mut distances: HashMap<(i32, i32), i32> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for (&key, &tile_type) in &tiles {
+        if tile_type == TileType::Water {
+            distances.insert(key, 0);
+            queue.push_back(key);
+        }
+    }
+    
+    while let Some((q, r)) = queue.pop_front() {
+        let distance = distances[&(q, r)];
+        if distance >= width {
+            continue;
+        }
+        for neighbor in get_hex_neighbors(q, r) {
+            if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                entry.insert(distance + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    
+    for (key, tile_type) in tiles.iter_mut() {
+        if *tile_type != TileType::Water && distances.contains_key(key) {
+            *tile_type = beach_type;
+        }
+    }
+    
+    tile_map_to_json(&tiles)
+}
+
+/// Find the shoreline of a tile map
+/// 
+/// **Learning Point**: The one-step special case of `erode_coastline`: a single pass
+/// keeps every non-water tile with at least one water tile among its six neighbors, with
+/// no BFS or distance bookkeeping. The map is returned untouched, so the caller decides
+/// what the shore becomes (e.g. sand via `set_pre_constraints_json`).
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @returns JSON array of shoreline coordinates sorted by (q, r): [{"q":0,"r":0},...]
+#[wasm_bindgen]
+pub fn detect_coastline(tiles_json: String) -> String {
+    let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&tiles_json).into_iter().collect();
+    
+    let mut coast: Vec<(i32, i32)> = tiles.iter()
+        .filter(|(_, &tile_type)| tile_type != TileType::Water)
+        .filter(|(&(q, r), _)| {
+            get_hex_neighbors(q, r)
+                .iter()
+                .any(|neighbor| tiles.get(neighbor) == Some(&TileType::Water))
+        })
+        .map(|(&key, _)| key)
+        .collect();
+    coast.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in coast {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, q, r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Shift every tile of a map by an axial offset
+/// 
+/// **Learning Point**: In axial coordinates a translation is plain addition on q and r,
+/// so a stamp keeps its exact shape wherever it is moved. Additions are checked: if any
+/// tile would leave the i32 range the whole call fails, since clamping tiles to the edge
+/// would silently merge them and distort the stamp.
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @param dq - Offset added to every q coordinate
+/// @param dr - Offset added to every r coordinate
+/// @returns JSON tile map sorted by (q, r): [{"q":0,"r":0,"tileType":N},...],
+///          or "null" if a shifted coordinate would overflow i32
+#[wasm_bindgen]
+pub fn translate_tile_map(tiles_json: String, dq: i32, dr: i32) -> String {
+    let shifted: Option<HashMap<(i32, i32), TileType>> = parse_tile_map_json(&tiles_json)
+        .into_iter()
+        .map(|((q, r), tile_type)| Some(((q.checked_add(dq)?, r.checked_add(dr)?), tile_type)))
+        .collect();
+    
+    match shifted {
+        Some(shifted) => tile_map_to_json(&shifted),
+        None => "null".to_string(),
+    }
+}
+
+/// Downsample a tile map to a coarser grid for minimaps and LOD
+/// 
+/// **Learning Point**: The hex analog of image downsampling. Each tile belongs to the
+/// super-hex `(q.div_euclid(factor), r.div_euclid(factor))` - floor division, so negative
+/// coordinates group the same way as positive ones. Each super-hex takes the majority
+/// tile type of its members; ties go to the lowest tile type value so the result is
+/// deterministic. The coarse map is returned in super-hex coordinates.
+/// 
+/// @param tiles_json - JSON tile map: [{"q":0,"r":0,"tileType":0},...]
+/// @param factor - Downsampling factor (must be >= 2)
+/// @returns JSON tile map in super-hex coordinates sorted by (q, r): [{"q":0,"r":0,"tileType":N},...],
+///          or "null" if factor < 2
+#[wasm_bindgen]
+pub fn downsample_grid(tiles_json: String, factor: i32) -> String {
+    if factor < 2 {
+        return "null".to_string();
+    }
+    
+    let tiles: HashMap<(i32, i32), TileType> = parse_tile_map_json(&tiles_json).into_iter().collect();
+    
+    let mut counts: HashMap<(i32, i32), [u32; TileType::COUNT]> = HashMap::new();
+    for (&(q, r), &tile_type) in &tiles {
+        let super_hex = (q.div_euclid(factor), r.div_euclid(factor));
+        counts.entry(super_hex).or_insert([0; TileType::COUNT])[tile_type as usize] += 1;
+    }
+    
+    let mut coarse: HashMap<(i32, i32), TileType> = HashMap::new();
+    for (super_hex, type_counts) in counts {
+        let mut best = 0;
+        for index in 1..type_counts.len() {
+            if type_counts[index] > type_counts[best] {
+                best = index;
+            }
+        }
+        if let Some(tile_type) = TileType::from_i32(best as i32) {
+            coarse.insert(super_hex, tile_type);
+        }
+    }
+    
+    tile_map_to_json(&coarse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_utils::{generate_hex_grid, get_hex_neighbors, hex_distance};
+    
+    #[test]
+    fn translate_shifts_every_tile_by_the_offset() {
+        let tiles = vec![((0, 0), TileType::Grass), ((1, -1), TileType::Water), ((-2, 3), TileType::Forest)];
+        let tiles_json = tile_map_to_json(&tiles.iter().copied().collect());
+        
+        let shifted = parse_tile_map_json(&translate_tile_map(tiles_json, 5, -3));
+        assert_eq!(shifted.len(), tiles.len());
+        for ((q, r), tile_type) in tiles {
+            assert!(shifted.contains(&((q + 5, r - 3), tile_type)), "{:?}", shifted);
+        }
+    }
+    
+    #[test]
+    fn translate_rejects_overflow() {
+        let tiles_json = format!(r#"[{{"q":{},"r":0,"tileType":0}},{{"q":0,"r":0,"tileType":1}}]"#, i32::MAX);
+        assert_eq!(translate_tile_map(tiles_json.clone(), 1, 0), "null");
+        assert_eq!(translate_tile_map(tiles_json, 0, i32::MIN), r#"[{"q":0,"r":-2147483648,"tileType":1},{"q":2147483647,"r":-2147483648,"tileType":0}]"#);
+    }
+    
+    #[test]
+    fn blend_end_points_match_from_and_to() {
+        let from = tile_map_to_json(&[((0, 0), TileType::Grass), ((1, 0), TileType::Water), ((2, 0), TileType::Road)].into_iter().collect());
+        let to = tile_map_to_json(&[((0, 0), TileType::Forest), ((1, 0), TileType::Water), ((0, 1), TileType::Building)].into_iter().collect());
+        
+        for seed in [0, 7, 12345] {
+            assert_eq!(blend_tile_maps(from.clone(), to.clone(), 0.0, seed), from);
+            assert_eq!(blend_tile_maps(from.clone(), to.clone(), 1.0, seed), to);
+        }
+    }
+    
+    #[test]
+    fn morton_order_is_deterministic_and_local() {
+        let grid = generate_hex_grid(8, 0, 0);
+        let tiles: HashMap<(i32, i32), TileType> = grid.iter().map(|hex| ((hex.q, hex.r), TileType::Grass)).collect();
+        let sorted_json = tile_map_to_json(&tiles);
+        let mut reversed = parse_tile_map_json(&sorted_json);
+        reversed.reverse();
+        let reversed_json = format!("[{}]", reversed.iter()
+            .map(|((q, r), tile_type)| format!(r#"{{"q":{},"r":{},"tileType":{}}}"#, q, r, *tile_type as i32))
+            .collect::<Vec<_>>()
+            .join(","));
+        
+        let ordered = grid_morton_order(sorted_json);
+        assert_eq!(ordered, grid_morton_order(reversed_json));
+        
+        // Neighboring tiles sit far closer in the output than random pairs would (~n/3)
+        let index: HashMap<(i32, i32), usize> = parse_tile_map_json(&ordered).into_iter()
+            .enumerate()
+            .map(|(i, (key, _))| (key, i))
+            .collect();
+        let gaps: Vec<usize> = index.iter()
+            .flat_map(|(&(q, r), &i)| get_hex_neighbors(q, r).into_iter()
+                .filter_map(|neighbor| index.get(&neighbor))
+                .map(move |&j| i.abs_diff(j)))
+            .collect();
+        let mean_gap = gaps.iter().sum::<usize>() as f64 / gaps.len() as f64;
+        assert!(mean_gap < index.len() as f64 / 10.0, "mean gap {}", mean_gap);
+    }
+    
+    #[test]
+    fn erode_converts_exactly_the_shore_band() {
+        // Water disk of radius 1 in a grass disk of radius 5
+        let tiles: HashMap<(i32, i32), TileType> = generate_hex_grid(5, 0, 0).iter()
+            .map(|hex| {
+                let tile_type = if hex_distance(0, 0, hex.q, hex.r) <= 1 { TileType::Water } else { TileType::Grass };
+                ((hex.q, hex.r), tile_type)
+            })
+            .collect();
+        
+        let width = 2;
+        let eroded = parse_tile_map_json(&erode_coastline(tile_map_to_json(&tiles), TileType::Road as i32, width));
+        assert_eq!(eroded.len(), tiles.len());
+        for ((q, r), tile_type) in eroded {
+            let expected = match hex_distance(0, 0, q, r) {
+                0..=1 => TileType::Water,
+                2..=3 => TileType::Road,
+                _ => TileType::Grass,
+            };
+            assert_eq!(tile_type, expected, "({}, {})", q, r);
+        }
+    }
+    
+    #[test]
+    fn uniform_block_downsamples_to_one_super_hex() {
+        let block: HashMap<(i32, i32), TileType> = (3..6).flat_map(|q| (-3..0).map(move |r| ((q, r), TileType::Forest))).collect();
+        assert_eq!(
+            downsample_grid(tile_map_to_json(&block), 3),
+            format!(r#"[{{"q":1,"r":-1,"tileType":{}}}]"#, TileType::Forest as i32)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 /// Core type definitions for the WASM module
 
-/// Tile type enumeration for 5 simple tile types
+/// Tile type enumeration for 7 simple tile types
 /// 
 /// **Learning Point**: Simplified tile types for hex grid layout generation.
 /// Each tile type represents a terrain or structure type.
@@ -12,6 +12,28 @@ pub enum TileType {
     Road = 2,
     Forest = 3,
     Water = 4,
+    Mountain = 5,
+    Sand = 6,
+}
+
+impl TileType {
+    /// Number of tile type variants (valid integers are 0..COUNT)
+    pub const COUNT: usize = 7;
+
+    /// Convert a JSON/JS tile type integer into a TileType
+    /// Returns None for integers outside the enum range
+    pub fn from_i32(value: i32) -> Option<TileType> {
+        match value {
+            0 => Some(TileType::Grass),
+            1 => Some(TileType::Building),
+            2 => Some(TileType::Road),
+            3 => Some(TileType::Forest),
+            4 => Some(TileType::Water),
+            5 => Some(TileType::Mountain),
+            6 => Some(TileType::Sand),
+            _ => None,
+        }
+    }
 }
 
 /// Hex coordinate structure for Voronoi generation
@@ -29,6 +51,104 @@ pub struct CubeCoord {
     pub s: i32,
 }
 
+/// Hexagon orientation for pixel-space conversions
+/// 
+/// **Learning Point**: Pointy-top hexes have a corner pointing up and rows offset
+/// horizontally; flat-top hexes have an edge on top and columns offset vertically.
+/// Both use the same axial coordinates - only the pixel mapping changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexOrientation {
+    PointyTop = 0,
+    FlatTop = 1,
+}
+
+impl HexOrientation {
+    /// Convert a JS orientation integer (0 = pointy-top, 1 = flat-top)
+    pub fn from_i32(value: i32) -> Option<HexOrientation> {
+        match value {
+            0 => Some(HexOrientation::PointyTop),
+            1 => Some(HexOrientation::FlatTop),
+            _ => None,
+        }
+    }
+}
+
+/// Offset coordinate layout used by dense row/column tilemaps
+/// 
+/// **Learning Point**: Offset coordinates shove every other row (pointy-top, `*R`)
+/// or column (flat-top, `*Q`) by half a hex so the grid fits a rectangle.
+/// "Odd" layouts shove the odd rows/columns, "even" layouts the even ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetType {
+    OddR = 0,
+    EvenR = 1,
+    OddQ = 2,
+    EvenQ = 3,
+}
+
+impl OffsetType {
+    /// Convert a JS offset type integer (0 = odd-r, 1 = even-r, 2 = odd-q, 3 = even-q)
+    pub fn from_i32(value: i32) -> Option<OffsetType> {
+        match value {
+            0 => Some(OffsetType::OddR),
+            1 => Some(OffsetType::EvenR),
+            2 => Some(OffsetType::OddQ),
+            3 => Some(OffsetType::EvenQ),
+            _ => None,
+        }
+    }
+}
+
+/// Distance metric used to assign hexes to their nearest Voronoi seed
+/// 
+/// **Learning Point**: The metric decides the shape of the region boundaries:
+/// cube distance gives hexagonal regions, Manhattan distance on raw axial q/r gives
+/// skewed diamond-like regions, and Euclidean distance between pixel centers gives
+/// round, organic-looking regions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceMetric {
+    Cube = 0,
+    AxialManhattan = 1,
+    PixelEuclidean = 2,
+}
+
+impl DistanceMetric {
+    /// Convert a JS metric integer (0 = cube, 1 = Manhattan on axial, 2 = squared Euclidean on pixels)
+    pub fn from_i32(value: i32) -> Option<DistanceMetric> {
+        match value {
+            0 => Some(DistanceMetric::Cube),
+            1 => Some(DistanceMetric::AxialManhattan),
+            2 => Some(DistanceMetric::PixelEuclidean),
+            _ => None,
+        }
+    }
+}
+
+/// Outline of a generated chunk grid
+/// 
+/// **Learning Point**: A hexagon is the natural disc around a center, but hexagons don't
+/// tile a world without gaps or overlap. Rectangles (rows shifted to follow the
+/// pointy-top zig-zag) and axial parallelograms do, so they are the shapes to use when
+/// chunks must sit edge to edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridShape {
+    Hexagon = 0,
+    Rectangle = 1,
+    Parallelogram = 2,
+}
+
+impl GridShape {
+    /// Convert a JS shape integer (0 = hexagon, 1 = rectangle, 2 = parallelogram)
+    pub fn from_i32(value: i32) -> Option<GridShape> {
+        match value {
+            0 => Some(GridShape::Hexagon),
+            1 => Some(GridShape::Rectangle),
+            2 => Some(GridShape::Parallelogram),
+            _ => None,
+        }
+    }
+}
+
 /// Seed point for Voronoi region generation
 #[derive(Clone, Copy, Debug)]
 pub struct VoronoiSeed {
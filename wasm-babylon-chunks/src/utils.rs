@@ -2,8 +2,63 @@
 
 use wasm_bindgen::prelude::*;
 use std::collections::HashSet;
-use crate::state::WFC_STATE;
-use crate::hex_utils::{parse_valid_terrain_json, get_hex_neighbors};
+use crate::state::{WFC_STATE, lock_or_recover};
+use crate::types::{CubeCoord, HexOrientation, TileType};
+use crate::hex_utils::{parse_coords, parse_int_fields_json, parse_valid_terrain_json, get_hex_neighbors, hex_distance, hex_center_pixel, pixel_to_axial, axial_to_cube, cube_ring, hex_spiral, hex_line, CUBE_DIRECTIONS};
+
+/// Format a float for JSON output with a fixed, locale-independent precision
+///
+/// Rounds to 6 significant digits, trims trailing zeros (and a trailing `.`),
+/// and always uses `.` as the decimal separator. Whole numbers print without a
+/// fractional part (`3.0` -> `"3"`), so snapshots stay stable across targets.
+/// Integer digits beyond the sixth are kept rather than rounded away.
+/// Non-finite values have no JSON representation and are emitted as `null`.
+///
+/// Examples: `0.1 + 0.2` -> `"0.3"`, `-0.0` -> `"0"`, `1.0 / 3.0` -> `"0.333333"`
+pub fn fmt_f64(v: f64) -> String {
+    if !v.is_finite() {
+        return "null".to_string();
+    }
+    if v == 0.0 {
+        return "0".to_string();
+    }
+    
+    // Number of decimals needed to keep 6 significant digits
+    let exponent = v.abs().log10().floor() as i32;
+    let decimals = (5 - exponent).max(0) as usize;
+    let mut formatted = format!("{:.*}", decimals, v);
+    
+    if formatted.contains('.') {
+        let trimmed_len = formatted.trim_end_matches('0').trim_end_matches('.').len();
+        formatted.truncate(trimmed_len);
+    }
+    
+    // Rounding tiny negatives can produce "-0"
+    if formatted == "-0" {
+        return "0".to_string();
+    }
+    
+    formatted
+}
+
+/// Deterministic per-coordinate random value in [0, 1)
+///
+/// Hashes (seed, q, r) with a SplitMix64-style finalizer, so each tile gets its own
+/// reproducible roll that doesn't depend on iteration order. Thresholding the roll
+/// against a probability is therefore monotonic: a tile that passes at `p` also passes
+/// at any larger `p`.
+pub fn coord_random(seed: u32, q: i32, r: i32) -> f64 {
+    let mix = |mut x: u64| -> u64 {
+        x = x.wrapping_add(0x9E3779B97F4A7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+        x ^ (x >> 31)
+    };
+    
+    let x = mix(mix(mix(seed as u64) ^ q as u32 as u64) ^ r as u32 as u64);
+    // Top 53 bits give a uniformly distributed f64 mantissa
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
 
 /// Batch query tile types for multiple hex coordinates
 /// Returns JSON array with tile types: [{"q":0,"r":0,"tileType":1},...]
@@ -12,7 +67,7 @@ use crate::hex_utils::{parse_valid_terrain_json, get_hex_neighbors};
 /// @returns JSON array with tile types for each coordinate
 #[wasm_bindgen]
 pub fn batch_get_tile_types(hex_coords_json: String) -> String {
-    let state = WFC_STATE.lock().unwrap();
+    let state = lock_or_recover(&WFC_STATE);
     
     // Parse hex coordinates
     let hex_coords = parse_valid_terrain_json(&hex_coords_json);
@@ -38,67 +93,11 @@ pub fn batch_get_tile_types(hex_coords_json: String) -> String {
 #[wasm_bindgen]
 pub fn shuffle_array(array_json: String) -> String {
     // Parse array
-    let mut coords: Vec<(i32, i32)> = Vec::new();
-    
-    let trimmed = array_json.trim();
-    if trimmed.is_empty() || trimmed == "[]" {
+    let mut coords = parse_coords(&array_json);
+    if coords.is_empty() {
         return "[]".to_string();
     }
     
-    // Simple JSON parsing: find all {"q":X,"r":Y} patterns
-    let mut i = 0;
-    let chars: Vec<char> = trimmed.chars().collect();
-    while i < chars.len() {
-        if chars[i] == '{' {
-            let mut q_value: Option<i32> = None;
-            let mut r_value: Option<i32> = None;
-            
-            i += 1;
-            while i < chars.len() && chars[i] != '}' {
-                if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'q' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            q_value = Some(num);
-                        }
-                    }
-                } else if i + 3 < chars.len() && chars[i] == '"' && chars[i + 1] == 'r' && chars[i + 2] == '"' {
-                    i += 3;
-                    while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                        i += 1;
-                    }
-                    if i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '-') {
-                        let start = i;
-                        i += 1;
-                        while i < chars.len() && chars[i].is_ascii_digit() {
-                            i += 1;
-                        }
-                        let num_str: String = chars[start..i].iter().collect();
-                        if let Ok(num) = num_str.parse::<i32>() {
-                            r_value = Some(num);
-                        }
-                    }
-                } else {
-                    i += 1;
-                }
-            }
-            
-            if let (Some(q), Some(r)) = (q_value, r_value) {
-                coords.push((q, r));
-            }
-        }
-        i += 1;
-    }
-    
     // Fisher-Yates shuffle using a simple PRNG
     // Use a deterministic seed based on array content for reproducibility
     let mut seed: u64 = 0;
@@ -234,39 +233,10 @@ pub fn generate_building_placement(
     let occupied_set: HashSet<(i32, i32)> = occupied.iter().cloned().collect();
     
     // Parse building rules
-    let mut min_adjacent_roads = 1;
-    let trimmed_rules = building_rules_json.trim();
-    if !trimmed_rules.is_empty() && trimmed_rules != "{}" {
-        // Simple JSON parsing for minAdjacentRoads
-        let chars: Vec<char> = trimmed_rules.chars().collect();
-        let mut i = 0;
-        while i < chars.len() {
-            if i + 18 < chars.len() && chars[i] == '"' && chars[i + 1] == 'm' && chars[i + 2] == 'i' 
-                && chars[i + 3] == 'n' && chars[i + 4] == 'A' && chars[i + 5] == 'd' 
-                && chars[i + 6] == 'j' && chars[i + 7] == 'a' && chars[i + 8] == 'c' 
-                && chars[i + 9] == 'e' && chars[i + 10] == 'n' && chars[i + 11] == 't' 
-                && chars[i + 12] == 'R' && chars[i + 13] == 'o' && chars[i + 14] == 'a' 
-                && chars[i + 15] == 'd' && chars[i + 16] == 's' && chars[i + 17] == '"' {
-                i += 18;
-                while i < chars.len() && (chars[i] == ':' || chars[i] == ' ' || chars[i] == '\t') {
-                    i += 1;
-                }
-                if i < chars.len() && chars[i].is_ascii_digit() {
-                    let start = i;
-                    i += 1;
-                    while i < chars.len() && chars[i].is_ascii_digit() {
-                        i += 1;
-                    }
-                    let num_str: String = chars[start..i].iter().collect();
-                    if let Ok(num) = num_str.parse::<i32>() {
-                        min_adjacent_roads = num;
-                    }
-                }
-                break;
-            }
-            i += 1;
-        }
-    }
+    let min_adjacent_roads = parse_int_fields_json(&building_rules_json, &["minAdjacentRoads"])
+        .first()
+        .map(|fields| fields[0])
+        .unwrap_or(1);
     
     // Find available hexes for buildings
     let mut available_building_hexes: Vec<(i32, i32)> = Vec::new();
@@ -352,10 +322,253 @@ pub fn batch_hex_to_world(hex_coords_json: String, hex_size: f64) -> String {
         
         json_parts.push(format!(
             r#"{{"q":{},"r":{},"x":{},"z":{}}}"#,
-            q, r, x, z
+            q, r, fmt_f64(x), fmt_f64(z)
         ));
     }
     
     format!("[{}]", json_parts.join(","))
 }
 
+/// Compute autotile connection masks for every grid tile of one type
+/// 
+/// **Learning Point**: Bit `i` of the mask is set when the neighbor in direction
+/// `CUBE_DIRECTIONS[i]` has the same tile type, giving a 6-bit value (0-63) the renderer
+/// can use to pick a road/wall/coast variant. Batching the whole grid into one call
+/// avoids thousands of per-tile FFI crossings on large maps.
+/// 
+/// @param tile_type - Tile type as i32 (0-6, matching TileType enum)
+/// @returns JSON array sorted by (q, r): [{"q":0,"r":0,"mask":5},...], or "null" if tile type is invalid
+#[wasm_bindgen]
+pub fn all_connection_masks(tile_type: i32) -> String {
+    let target = match TileType::from_i32(tile_type) {
+        Some(tile) => tile,
+        None => return "null".to_string(),
+    };
+    
+    let state = lock_or_recover(&WFC_STATE);
+    
+    let mut tiles: Vec<(i32, i32)> = state.grid_tiles()
+        .filter(|(_, tile)| *tile == target)
+        .map(|(key, _)| key)
+        .collect();
+    tiles.sort();
+    
+    let mut json_parts = Vec::new();
+    for (q, r) in tiles {
+        let mut mask = 0;
+        for (direction, offset) in CUBE_DIRECTIONS.iter().enumerate() {
+            if state.get_tile(q + offset.q, r + offset.r) == Some(target) {
+                mask |= 1 << direction;
+            }
+        }
+        json_parts.push(format!(r#"{{"q":{},"r":{},"mask":{}}}"#, q, r, mask));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Hex distance between two axial coordinates, exposed to JavaScript
+/// 
+/// **Learning Point**: Returns exactly the value the pathfinder and generators use
+/// internally (cube distance), so JS rendering code never drifts out of sync with it.
+/// 
+/// @param q1 - First q coordinate
+/// @param r1 - First r coordinate
+/// @param q2 - Second q coordinate
+/// @param r2 - Second r coordinate
+/// @returns Number of hex steps between the two hexes (saturates at i32::MAX for extreme coordinates)
+#[wasm_bindgen]
+pub fn hex_distance_js(q1: i32, r1: i32, q2: i32, r2: i32) -> i32 {
+    hex_distance(q1, r1, q2, r2)
+}
+
+/// Get the six neighbors of a hex, exposed to JavaScript
+/// 
+/// **Learning Point**: Returns exactly what `get_hex_neighbors` gives the pathfinder, in
+/// the same order: `CUBE_DIRECTIONS` order, so that index `i` is direction `i` -
+/// the same numbering used by connection masks and boundary edges:
+/// 0 = (+1, 0), 1 = (+1, -1), 2 = (0, -1), 3 = (-1, 0), 4 = (-1, +1), 5 = (0, +1)
+/// 
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @returns JSON array of 6 neighbors in direction order: [{"q":1,"r":0},...]
+#[wasm_bindgen]
+pub fn hex_neighbors_json(q: i32, r: i32) -> String {
+    let mut json_parts = Vec::new();
+    for (neighbor_q, neighbor_r) in get_hex_neighbors(q, r) {
+        json_parts.push(format!(r#"{{"q":{},"r":{}}}"#, neighbor_q, neighbor_r));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Format cube coordinates as a JSON array of axial hexes, keeping their order
+fn cubes_to_json(cubes: &[CubeCoord]) -> String {
+    let json_parts: Vec<String> = cubes.iter()
+        .map(|cube| format!(r#"{{"q":{},"r":{}}}"#, cube.q, cube.r))
+        .collect();
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Get the ring of hexes at an exact distance from a center, in traversal order
+/// 
+/// **Learning Point**: The ring starts `radius` steps from the center in direction
+/// `CUBE_DIRECTIONS[4]` (-1, +1) and walks its six sides in `CUBE_DIRECTIONS` order
+/// 0 through 5, `radius` steps per side. Consecutive entries are neighbors, so
+/// revealing the list in order animates a continuous sweep around the ring.
+/// 
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param radius - Ring radius in hex steps
+/// @returns JSON array of 6 * radius hexes in traversal order: [{"q":0,"r":0},...],
+///          just the center for radius 0, or "[]" if radius is negative
+#[wasm_bindgen]
+pub fn hex_ring_json(center_q: i32, center_r: i32, radius: i32) -> String {
+    if radius < 0 {
+        return "[]".to_string();
+    }
+    cubes_to_json(&cube_ring(axial_to_cube(center_q, center_r), radius))
+}
+
+/// Get every hex within a radius of a center, from the center outward in spiral order
+/// 
+/// **Learning Point**: The center comes first, followed by each ring from radius 1 to
+/// `max_radius` in the same order `hex_ring_json` returns it. Ring n therefore starts at
+/// index 3n(n - 1) + 1, which lets an expanding-map animation slice out one ring at a time.
+/// 
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param max_radius - Outermost ring to include
+/// @returns JSON array of 3n(n + 1) + 1 hexes in spiral order: [{"q":0,"r":0},...],
+///          or "[]" if max_radius is negative
+#[wasm_bindgen]
+pub fn hex_spiral_json(center_q: i32, center_r: i32, max_radius: i32) -> String {
+    cubes_to_json(&hex_spiral(axial_to_cube(center_q, center_r), max_radius))
+}
+
+/// Draw a straight line of hexes between two points
+/// 
+/// **Learning Point**: Standard cube-coordinate line drawing: the line is sampled at
+/// `hex_distance + 1` evenly spaced points and each sample is snapped to a hex with cube
+/// rounding. Every consecutive pair in the result is adjacent, so the line is a valid
+/// road path of minimal length - just a straight one instead of whatever A* happens to pick.
+/// 
+/// @param q1 - Start q coordinate
+/// @param r1 - Start r coordinate
+/// @param q2 - End q coordinate
+/// @param r2 - End r coordinate
+/// @returns JSON array of hexes from start to end inclusive: [{"q":0,"r":0},...],
+///          a single hex when start equals end
+#[wasm_bindgen]
+pub fn hex_line_json(q1: i32, r1: i32, q2: i32, r2: i32) -> String {
+    let json_parts: Vec<String> = hex_line(q1, r1, q2, r2).iter()
+        .map(|(q, r)| format!(r#"{{"q":{},"r":{}}}"#, q, r))
+        .collect();
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Convert axial coordinates to the pixel position of the hex center
+/// 
+/// **Learning Point**: Uses the pointy-top layout (a corner points up, rows of hexes
+/// run horizontally), matching `hex_corners` and the world placement in
+/// `batch_hex_to_world`. Screen convention: x grows right, y grows down.
+/// x = size * √3 * (q + r/2), y = size * 3/2 * r, where `size` is the center-to-corner
+/// radius. `pixel_to_hex` is the exact inverse.
+/// 
+/// @param q - Hex q coordinate
+/// @param r - Hex r coordinate
+/// @param size - Hex radius (center to corner) in pixels
+/// @returns JSON object with the center position: {"x":0,"y":0}
+#[wasm_bindgen]
+pub fn hex_to_pixel(q: i32, r: i32, size: f64) -> String {
+    let (x, y) = hex_center_pixel(q, r, size, HexOrientation::PointyTop);
+    format!(r#"{{"x":{},"y":{}}}"#, fmt_f64(x), fmt_f64(y))
+}
+
+/// Find the hex containing a pixel position
+/// 
+/// **Learning Point**: Inverts the pointy-top `hex_to_pixel` transform to get fractional
+/// axial coordinates, then applies cube rounding: all three cube components are rounded
+/// and the one with the largest rounding error is recomputed from the other two, so
+/// points near a corner snap to the hex that actually contains them instead of an
+/// invalid (q + r + s != 0) coordinate. `pixel_to_hex(hex_to_pixel(q, r))` returns (q, r).
+/// 
+/// @param x - Pixel x (grows right)
+/// @param y - Pixel y (grows down)
+/// @param size - Hex radius (center to corner) in pixels, must be > 0
+/// @returns JSON object with the hex: {"q":0,"r":0}, or "null" if size <= 0
+#[wasm_bindgen]
+pub fn pixel_to_hex(x: f64, y: f64, size: f64) -> String {
+    if size <= 0.0 || !size.is_finite() {
+        return "null".to_string();
+    }
+    
+    let (q, r) = pixel_to_axial(x, y, size, HexOrientation::PointyTop);
+    format!(r#"{{"q":{},"r":{}}}"#, q, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn hex_neighbors_json_matches_get_hex_neighbors() {
+        let expected: Vec<String> = get_hex_neighbors(2, -3)
+            .into_iter()
+            .map(|(q, r)| format!(r#"{{"q":{},"r":{}}}"#, q, r))
+            .collect();
+        assert_eq!(hex_neighbors_json(2, -3), format!("[{}]", expected.join(",")));
+        assert_eq!(get_hex_neighbors(0, 0)[1], (1, -1));
+    }
+    
+    #[test]
+    fn fmt_f64_is_stable() {
+        assert_eq!(fmt_f64(0.1 + 0.2), "0.3");
+        assert_eq!(fmt_f64(3.0), "3");
+        assert_eq!(fmt_f64(-2.0), "-2");
+        assert_eq!(fmt_f64(1234567.0), "1234567");
+    }
+    
+    #[test]
+    fn connection_masks_match_road_connections() {
+        let _guard = lock_or_recover(&crate::state::STATE_TEST_LOCK);
+        let roads = [(-1, 0), (0, 0), (1, 0), (1, 1), (3, 3)];
+        {
+            let mut state = lock_or_recover(&WFC_STATE);
+            state.clear();
+            for &(q, r) in &roads {
+                state.insert_tile(q, r, TileType::Road);
+            }
+            state.insert_tile(0, 1, TileType::Grass);
+        }
+        
+        let masks = parse_int_fields_json(&all_connection_masks(TileType::Road as i32), &["q", "r", "mask"]);
+        assert_eq!(masks.len(), roads.len());
+        for fields in masks {
+            let expected = CUBE_DIRECTIONS.iter().enumerate()
+                .filter(|(_, offset)| roads.contains(&(fields[0] + offset.q, fields[1] + offset.r)))
+                .fold(0, |mask, (direction, _)| mask | (1 << direction));
+            assert_eq!(fields[2], expected, "{:?}", fields);
+        }
+        // (0,0) connects east (direction 0) and west (direction 3)
+        assert!(all_connection_masks(TileType::Road as i32).contains(r#"{"q":0,"r":0,"mask":9}"#));
+        
+        lock_or_recover(&WFC_STATE).clear();
+    }
+    
+    #[test]
+    fn pixel_to_hex_inverts_hex_to_pixel() {
+        for size in [1.0, 10.0, 32.5] {
+            for q in -20..=20 {
+                for r in -20..=20 {
+                    let center: serde_json::Value = serde_json::from_str(&hex_to_pixel(q, r, size)).unwrap();
+                    let (x, y) = (center["x"].as_f64().unwrap(), center["y"].as_f64().unwrap());
+                    let expected = format!(r#"{{"q":{},"r":{}}}"#, q, r);
+                    assert_eq!(pixel_to_hex(x, y, size), expected);
+                    // Points well inside the hex snap back to it too
+                    assert_eq!(pixel_to_hex(x + 0.4 * size, y - 0.4 * size, size), expected);
+                }
+            }
+        }
+    }
+}
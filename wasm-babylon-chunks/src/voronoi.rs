@@ -1,13 +1,16 @@
 /// Voronoi region generation module
 
 use wasm_bindgen::prelude::*;
-use crate::types::{TileType, VoronoiSeed};
-use crate::hex_utils::{generate_hex_grid, hex_distance};
+use crate::types::{DistanceMetric, GridShape, TileType, VoronoiSeed};
+use crate::hex_utils::{generate_grid, generate_hex_grid, hex_distance, cube_round, parse_tile_map_json};
+use crate::utils::coord_random;
 
 /// Generate Voronoi regions for specified tile types
 /// 
 /// **Learning Point**: Generates seed points for each region type and assigns
 /// each hex tile to the nearest seed point, creating Voronoi regions.
+/// Seed positions are drawn from `seed`, so the same arguments always give the same
+/// map; pass a new seed for a new map.
 /// Returns JSON string with array of {q, r, tileType} objects.
 /// 
 /// @param max_layer - Maximum layer of hexagon (determines grid size)
@@ -16,8 +19,18 @@ use crate::hex_utils::{generate_hex_grid, hex_distance};
 /// @param forest_seeds - Number of forest region seeds
 /// @param water_seeds - Number of water region seeds
 /// @param grass_seeds - Number of grass region seeds
-/// @returns JSON string with array of pre-constraints: [{"q":0,"r":0,"tileType":3},...]
+/// @param mountain_seeds - Number of mountain region seeds
+/// @param sand_seeds - Number of sand region seeds
+/// @param metric - Nearest-seed distance metric: 0 = cube distance (hexagonal regions),
+///                 1 = Manhattan on axial q/r, 2 = squared Euclidean on pixel coordinates
+///                 (round regions)
+/// @param shape - Grid outline: 0 = hexagon, 1 = rectangle, 2 = parallelogram (bounds as in
+///                `generate_grid`)
+/// @param seed - Seed for the region seed positions
+/// @returns JSON string with array of pre-constraints: [{"q":0,"r":0,"tileType":3},...],
+///          or "null" if metric or shape is invalid
 #[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
 pub fn generate_voronoi_regions(
     max_layer: i32,
     center_q: i32,
@@ -25,80 +38,318 @@ pub fn generate_voronoi_regions(
     forest_seeds: i32,
     water_seeds: i32,
     grass_seeds: i32,
+    mountain_seeds: i32,
+    sand_seeds: i32,
+    metric: i32,
+    shape: i32,
+    seed: u32,
 ) -> String {
-    // Generate hex grid
-    let hex_grid = generate_hex_grid(max_layer, center_q, center_r);
-    
-    // Early return pattern matching for error cases
-    let hex_vec: Vec<(i32, i32)> = match hex_grid.as_slice() {
-        [] => {
-            // If grid is empty, return at least one default entry
-            return r#"[{"q":0,"r":0,"tileType":0}]"#.to_string();
-        },
-        _ => hex_grid.iter().map(|h| (h.q, h.r)).collect(),
+    build_voronoi_regions(
+        max_layer, center_q, center_r,
+        forest_seeds, water_seeds, grass_seeds, mountain_seeds, sand_seeds,
+        0, metric, shape, seed,
+    )
+}
+
+/// Generate Voronoi regions with Lloyd's relaxation applied to the seeds
+/// 
+/// **Learning Point**: The seeded random placement can clump seeds together,
+/// producing a few huge regions next to tiny ones. Lloyd's relaxation evens them out:
+/// assign every hex to its nearest seed, move each seed to the centroid of its region
+/// (average q and r, cube-rounded to the nearest hex), and repeat. Each iteration pulls
+/// crowded seeds apart, so region sizes converge toward equal. A seed whose region is
+/// empty stays where it is. With 0 iterations the output is identical to
+/// `generate_voronoi_regions` with the same seed.
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param forest_seeds - Number of forest region seeds
+/// @param water_seeds - Number of water region seeds
+/// @param grass_seeds - Number of grass region seeds
+/// @param mountain_seeds - Number of mountain region seeds
+/// @param sand_seeds - Number of sand region seeds
+/// @param relaxation_iterations - Number of relaxation passes (values <= 0 disable relaxation)
+/// @param metric - Nearest-seed distance metric, as in `generate_voronoi_regions`
+/// @param shape - Grid outline, as in `generate_voronoi_regions`
+/// @param seed - Seed for the region seed positions, as in `generate_voronoi_regions`
+/// @returns JSON string with array of pre-constraints: [{"q":0,"r":0,"tileType":3},...],
+///          or "null" if metric or shape is invalid
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_voronoi_regions_relaxed(
+    max_layer: i32,
+    center_q: i32,
+    center_r: i32,
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+    mountain_seeds: i32,
+    sand_seeds: i32,
+    relaxation_iterations: i32,
+    metric: i32,
+    shape: i32,
+    seed: u32,
+) -> String {
+    build_voronoi_regions(
+        max_layer, center_q, center_r,
+        forest_seeds, water_seeds, grass_seeds, mountain_seeds, sand_seeds,
+        relaxation_iterations, metric, shape, seed,
+    )
+}
+
+/// Generate Voronoi regions as a packed integer array
+/// 
+/// **Learning Point**: Same generation as `generate_voronoi_regions`, but returned as a flat
+/// `[q, r, tileType, q, r, tileType, ...]` array. wasm-bindgen hands a `Vec<i32>` to JS
+/// as an `Int32Array` copied straight out of WASM memory, so large chunks skip both
+/// building the JSON string and `JSON.parse` on the main thread. Entry `i` is at
+/// indices `3i`, `3i + 1` and `3i + 2`; entries are sorted by (q, r).
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param forest_seeds - Number of forest region seeds
+/// @param water_seeds - Number of water region seeds
+/// @param grass_seeds - Number of grass region seeds
+/// @param mountain_seeds - Number of mountain region seeds
+/// @param sand_seeds - Number of sand region seeds
+/// @param metric - Nearest-seed distance metric, as in `generate_voronoi_regions`
+/// @param shape - Grid outline, as in `generate_voronoi_regions`
+/// @param seed - Seed for the region seed positions, as in `generate_voronoi_regions`
+/// @returns Int32Array of (q, r, tileType) triples (empty if the grid is empty or metric
+///          or shape is invalid)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_voronoi_regions_packed(
+    max_layer: i32,
+    center_q: i32,
+    center_r: i32,
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+    mountain_seeds: i32,
+    sand_seeds: i32,
+    metric: i32,
+    shape: i32,
+    seed: u32,
+) -> Vec<i32> {
+    let metric = match DistanceMetric::from_i32(metric) {
+        Some(metric) => metric,
+        None => return Vec::new(),
+    };
+    let shape = match GridShape::from_i32(shape) {
+        Some(shape) => shape,
+        None => return Vec::new(),
     };
+    let tiles = voronoi_tiles(
+        max_layer, center_q, center_r,
+        forest_seeds, water_seeds, grass_seeds, mountain_seeds, sand_seeds,
+        0, metric, shape, seed,
+    );
     
-    let hex_count = hex_vec.len();
-    match hex_count {
-        0 => {
-            // If hex_vec is empty, return at least one default entry
-            return r#"[{"q":0,"r":0,"tileType":0}]"#.to_string();
-        },
-        _ => {},
+    let mut packed = Vec::with_capacity(tiles.len() * 3);
+    for ((q, r), tile_type) in tiles {
+        packed.extend_from_slice(&[q, r, tile_type as i32]);
     }
+    packed
+}
+
+/// Generate Voronoi regions around caller-placed seeds
+/// 
+/// **Learning Point**: `generate_voronoi_regions` picks seed positions itself; here the
+/// caller places them (a lake center, a forest center) and each grid hex takes the tile
+/// type of the nearest seed by hex distance, using the same assignment step. Seeds do not
+/// have to lie on the grid: one outside it still pulls the nearby edge hexes into its
+/// region. When two seeds are equally near, the one listed first wins. Seeds with an
+/// invalid tile type are skipped.
+/// 
+/// @param max_layer - Maximum layer of hexagon (determines grid size)
+/// @param center_q - Center q coordinate
+/// @param center_r - Center r coordinate
+/// @param seeds_json - JSON array of seeds: [{"q":0,"r":0,"tileType":4},...] (tileType 0-6)
+/// @returns JSON string with array of pre-constraints sorted by (q, r): [{"q":0,"r":0,"tileType":3},...],
+///          or "[]" if there are no valid seeds
+#[wasm_bindgen]
+pub fn generate_voronoi_from_seeds(
+    max_layer: i32,
+    center_q: i32,
+    center_r: i32,
+    seeds_json: String,
+) -> String {
+    let seeds: Vec<VoronoiSeed> = parse_tile_map_json(&seeds_json)
+        .into_iter()
+        .map(|((q, r), tile_type)| VoronoiSeed { q, r, tile_type })
+        .collect();
     
-    // Generate seed points by sampling from actual hex grid coordinates
-    // Use deterministic selection with prime multiplier for good distribution
-    // This ensures seeds are ALWAYS generated reliably
-    let mut seeds: Vec<VoronoiSeed> = Vec::new();
-    let mut seed_counter: usize = 0;
-    
-    // Generate forest seeds
-    // Ensure we have at least 0 seeds (handle negative values)
-    let forest_count = if forest_seeds > 0 { forest_seeds as usize } else { 0 };
-    for i in 0..forest_count {
-        seed_counter += 1;
-        // Use deterministic selection: (counter * prime) % count for good distribution
-        // Prime 7919 provides good pseudo-random distribution
-        let index = ((seed_counter * 7919) + (i * 997)) % hex_count;
-        // Bounds check (should always pass due to modulo, but be safe)
-        if index < hex_vec.len() {
-            let (q, r) = hex_vec[index];
-            seeds.push(VoronoiSeed {
-                q,
-                r,
-                tile_type: TileType::Forest,
-            });
-        }
+    let mut hex_vec: Vec<(i32, i32)> = generate_hex_grid(max_layer, center_q, center_r)
+        .iter()
+        .map(|h| (h.q, h.r))
+        .collect();
+    hex_vec.sort();
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in assign_to_seeds(&hex_vec, &seeds, DistanceMetric::Cube) {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"tileType":{}}}"#,
+            q, r, tile_type as i32
+        ));
     }
     
-    // Generate water seeds
-    let water_count = if water_seeds > 0 { water_seeds as usize } else { 0 };
-    for i in 0..water_count {
-        seed_counter += 1;
-        let index = ((seed_counter * 7919) + (i * 997)) % hex_count;
-        if index < hex_vec.len() {
-            let (q, r) = hex_vec[index];
-            seeds.push(VoronoiSeed {
-                q,
-                r,
-                tile_type: TileType::Water,
-            });
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Distance from a hex to a seed under the chosen metric (smaller is nearer)
+/// 
+/// **Learning Point**: Axial coordinates are not a Cartesian frame, so Euclidean distance
+/// has to be measured between pixel centers for regions to come out round rather than
+/// hex-aligned. For pointy-top centers the squared pixel distance is
+/// 3 * size² * (dq² + dq·dr + dr²); the constant factor never changes which seed is
+/// nearest, so only the integer part is compared and no floats are needed. Differences
+/// are taken in i128 so even coordinates at opposite ends of the i32 range cannot overflow.
+fn seed_distance(metric: DistanceMetric, q: i32, r: i32, seed_q: i32, seed_r: i32) -> i128 {
+    let dq = q as i128 - seed_q as i128;
+    let dr = r as i128 - seed_r as i128;
+    match metric {
+        DistanceMetric::Cube => hex_distance(q, r, seed_q, seed_r) as i128,
+        DistanceMetric::AxialManhattan => dq.abs() + dr.abs(),
+        DistanceMetric::PixelEuclidean => dq * dq + dq * dr + dr * dr,
+    }
+}
+
+/// Move each seed to the centroid of the hexes nearest to it, `iterations` times
+fn relax_seeds(hex_vec: &[(i32, i32)], seeds: &mut [VoronoiSeed], iterations: i32, metric: DistanceMetric) {
+    for _ in 0..iterations {
+        // Per-seed sums of q and r plus member count
+        let mut sums: Vec<(i64, i64, i64)> = vec![(0, 0, 0); seeds.len()];
+        for &(q, r) in hex_vec {
+            let nearest = seeds.iter()
+                .enumerate()
+                .min_by_key(|(_, seed)| seed_distance(metric, q, r, seed.q, seed.r))
+                .map(|(index, _)| index);
+            if let Some(index) = nearest {
+                sums[index].0 += q as i64;
+                sums[index].1 += r as i64;
+                sums[index].2 += 1;
+            }
+        }
+        
+        let mut moved = false;
+        for (seed, &(sum_q, sum_r, count)) in seeds.iter_mut().zip(&sums) {
+            if count == 0 {
+                continue;
+            }
+            let (q, r) = cube_round(sum_q as f64 / count as f64, sum_r as f64 / count as f64);
+            if (q, r) != (seed.q, seed.r) {
+                seed.q = q;
+                seed.r = r;
+                moved = true;
+            }
+        }
+        
+        // Converged - further iterations would not change anything
+        if !moved {
+            break;
         }
     }
+}
+
+/// Shared implementation of the Voronoi region generators
+#[allow(clippy::too_many_arguments)]
+fn build_voronoi_regions(
+    max_layer: i32,
+    center_q: i32,
+    center_r: i32,
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+    mountain_seeds: i32,
+    sand_seeds: i32,
+    relaxation_iterations: i32,
+    metric: i32,
+    shape: i32,
+    seed: u32,
+) -> String {
+    let metric = match DistanceMetric::from_i32(metric) {
+        Some(metric) => metric,
+        None => return "null".to_string(),
+    };
+    let shape = match GridShape::from_i32(shape) {
+        Some(shape) => shape,
+        None => return "null".to_string(),
+    };
+    let tiles = voronoi_tiles(
+        max_layer, center_q, center_r,
+        forest_seeds, water_seeds, grass_seeds, mountain_seeds, sand_seeds,
+        relaxation_iterations, metric, shape, seed,
+    );
     
-    // Generate grass seeds
-    let grass_count = if grass_seeds > 0 { grass_seeds as usize } else { 0 };
-    for i in 0..grass_count {
-        seed_counter += 1;
-        let index = ((seed_counter * 7919) + (i * 997)) % hex_count;
-        if index < hex_vec.len() {
+    // Never return an empty array - callers expect at least one entry
+    if tiles.is_empty() {
+        return r#"[{"q":0,"r":0,"tileType":0}]"#.to_string();
+    }
+    
+    let mut json_parts = Vec::new();
+    for ((q, r), tile_type) in tiles {
+        json_parts.push(format!(
+            r#"{{"q":{},"r":{},"tileType":{}}}"#,
+            q, r, tile_type as i32
+        ));
+    }
+    
+    format!("[{}]", json_parts.join(","))
+}
+
+/// Assign every hex of the grid to its nearest Voronoi seed
+/// Seed positions are drawn with `coord_random(seed, i, -1 - tile type)`, so the same
+/// `seed` always gives the same map; the negative rows keep these rolls apart from the
+/// row-0 rolls `generate_map` uses for road seeds.
+/// Returns one (coordinate, tile type) entry per hex, in grid order; empty if the grid is empty
+#[allow(clippy::too_many_arguments)]
+pub fn voronoi_tiles(
+    max_layer: i32,
+    center_q: i32,
+    center_r: i32,
+    forest_seeds: i32,
+    water_seeds: i32,
+    grass_seeds: i32,
+    mountain_seeds: i32,
+    sand_seeds: i32,
+    relaxation_iterations: i32,
+    metric: DistanceMetric,
+    shape: GridShape,
+    seed: u32,
+) -> Vec<((i32, i32), TileType)> {
+    // Generate grid, sorted so seed selection by index depends only on `seed`
+    // (generate_hex_grid returns hexes in HashSet order)
+    let mut hex_vec: Vec<(i32, i32)> = generate_grid(shape, max_layer, center_q, center_r)
+        .iter()
+        .map(|h| (h.q, h.r))
+        .collect();
+    hex_vec.sort();
+    let hex_count = hex_vec.len();
+    if hex_count == 0 {
+        return Vec::new();
+    }
+    
+    // Generate seed points by sampling from actual hex grid coordinates
+    // Each (index, tile type) pair gets its own roll, so changing one count never moves
+    // the seeds of the other types
+    let mut seeds: Vec<VoronoiSeed> = Vec::new();
+    let seed_counts = [
+        (forest_seeds, TileType::Forest),
+        (water_seeds, TileType::Water),
+        (grass_seeds, TileType::Grass),
+        (mountain_seeds, TileType::Mountain),
+        (sand_seeds, TileType::Sand),
+    ];
+    for (count, tile_type) in seed_counts {
+        // Negative counts place no seeds
+        for i in 0..count.max(0) {
+            let roll = coord_random(seed, i, -1 - tile_type as i32);
+            let index = ((roll * hex_count as f64) as usize).min(hex_count - 1);
             let (q, r) = hex_vec[index];
-            seeds.push(VoronoiSeed {
-                q,
-                r,
-                tile_type: TileType::Grass,
-            });
+            seeds.push(VoronoiSeed { q, r, tile_type });
         }
     }
     
@@ -114,64 +365,71 @@ pub fn generate_voronoi_regions(
                         tile_type: TileType::Grass,
                     });
                 },
-                None => return r#"[{"q":0,"r":0,"tileType":0}]"#.to_string(),
+                None => return Vec::new(),
             }
         },
         _ => {},
     }
     
-    // Assign each hex to nearest seed and build JSON
-    // Ensure seeds is not empty (should be guaranteed by fallback above)
-    let seeds_ref = match seeds.as_slice() {
-        [] => return r#"[{"q":0,"r":0,"tileType":0}]"#.to_string(),
-        s => s,
-    };
+    // Spread clumped seeds apart before the final assignment
+    relax_seeds(&hex_vec, &mut seeds, relaxation_iterations, metric);
     
-    let mut json_parts = Vec::new();
-    for hex in &hex_grid {
-        let nearest_seed = seeds_ref.iter()
-            .min_by_key(|seed| hex_distance(hex.q, hex.r, seed.q, seed.r));
-        
-        match nearest_seed {
-            Some(seed) => {
-                json_parts.push(format!(
-                    r#"{{"q":{},"r":{},"tileType":{}}}"#,
-                    hex.q, hex.r, seed.tile_type as i32
-                ));
-            },
-            None => {},
+    assign_to_seeds(&hex_vec, &seeds, metric)
+}
+
+/// Give each hex the tile type of its nearest seed (ties go to the earlier seed)
+/// Returns entries in `hex_vec` order; empty if there are no seeds
+fn assign_to_seeds(hex_vec: &[(i32, i32)], seeds: &[VoronoiSeed], metric: DistanceMetric) -> Vec<((i32, i32), TileType)> {
+    let mut tiles = Vec::with_capacity(hex_vec.len());
+    for &(q, r) in hex_vec {
+        let nearest_seed = seeds.iter()
+            .min_by_key(|seed| seed_distance(metric, q, r, seed.q, seed.r));
+        if let Some(seed) = nearest_seed {
+            tiles.push(((q, r), seed.tile_type));
         }
     }
     
-    // If json_parts is empty (shouldn't happen), return at least one entry from first seed
-    let json_parts = match json_parts.as_slice() {
-        [] => {
-            match (seeds_ref.first(), hex_grid.first()) {
-                (Some(first_seed), _) => vec![format!(
-                    r#"{{"q":{},"r":{},"tileType":{}}}"#,
-                    first_seed.q, first_seed.r, first_seed.tile_type as i32
-                )],
-                (None, Some(first_hex)) => vec![format!(
-                    r#"{{"q":{},"r":{},"tileType":0}}"#,
-                    first_hex.q, first_hex.r
-                )],
-                (None, None) => return r#"[{"q":666,"r":666,"tileType":0}]"#.to_string(),
-            }
-        },
-        parts => parts.to_vec(),
-    };
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
     
-    // Final safety check - ensure we never return empty array
-    match json_parts.as_slice() {
-        [] => return r#"[{"q":555,"r":555,"tileType":0}]"#.to_string(),
-        _ => {},
+    #[test]
+    fn seed_distance_does_not_overflow_far_apart() {
+        let far = seed_distance(DistanceMetric::AxialManhattan, i32::MAX, i32::MAX, i32::MIN, i32::MIN);
+        assert_eq!(far, 2 * (u32::MAX as i128));
+        let round = seed_distance(DistanceMetric::PixelEuclidean, i32::MAX, 0, i32::MIN, 0);
+        assert_eq!(round, (u32::MAX as i128) * (u32::MAX as i128));
     }
     
-    let result = format!("[{}]", json_parts.join(","));
-    // Final check - if result is somehow "[]", return test value
-    match result.as_str() {
-        "[]" => r#"[{"q":444,"r":444,"tileType":0}]"#.to_string(),
-        _ => result,
+    #[test]
+    fn same_seed_repeats_and_new_seed_varies() {
+        let map = |seed| generate_voronoi_regions(5, 0, 0, 4, 2, 6, 0, 0, 0, 0, seed);
+        assert_eq!(map(7), map(7));
+        let maps: HashSet<String> = (0..8).map(map).collect();
+        assert!(maps.len() > 1);
+    }
+    
+    #[test]
+    fn zero_relaxation_matches_unrelaxed_regions() {
+        for seed in [0, 7, 12345] {
+            for (metric, shape) in [(0, 0), (2, 0), (1, 1)] {
+                assert_eq!(
+                    generate_voronoi_regions_relaxed(5, 0, 0, 4, 2, 6, 1, 1, 0, metric, shape, seed),
+                    generate_voronoi_regions(5, 0, 0, 4, 2, 6, 1, 1, metric, shape, seed),
+                    "seed {} metric {} shape {}", seed, metric, shape,
+                );
+            }
+        }
+    }
+    
+    #[test]
+    fn invalid_metric_is_rejected() {
+        assert_eq!(generate_voronoi_regions(2, 0, 0, 1, 1, 1, 0, 0, 9, 0, 0), "null");
+        assert!(generate_voronoi_regions_packed(2, 0, 0, 1, 1, 1, 0, 0, -1, 0, 0).is_empty());
+        assert_eq!(generate_voronoi_regions_relaxed(2, 0, 0, 1, 1, 1, 0, 0, 0, 0, 3, 0), "null");
     }
 }
-
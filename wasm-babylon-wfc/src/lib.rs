@@ -1,5 +1,5 @@
 use wasm_bindgen::prelude::*;
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, MutexGuard};
 use std::collections::{HashMap, HashSet, BinaryHeap};
 use std::cmp::Ordering;
 
@@ -855,6 +855,11 @@ pub fn generate_voronoi_regions(
 
 static WFC_STATE: LazyLock<Mutex<WfcState>> = LazyLock::new(|| Mutex::new(WfcState::new()));
 
+/// Lock a state mutex, recovering it if a previous call panicked while holding it
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 /// Initialize the WASM module
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -868,7 +873,7 @@ pub fn init() {
 /// 2. Fill any remaining empty cells with grass (shouldn't happen if pre-constraints are complete)
 #[wasm_bindgen]
 pub fn generate_layout() {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     state.clear();
     
     // Step 1: Apply pre-constraints to grid
@@ -893,7 +898,7 @@ pub fn generate_layout() {
 /// @returns Tile type as i32, or -1 if invalid/empty
 #[wasm_bindgen]
 pub fn get_tile_at(q: i32, r: i32) -> i32 {
-    let state = WFC_STATE.lock().unwrap();
+    let state = lock_or_recover(&WFC_STATE);
     if let Some(tile) = state.get_tile(q, r) {
         tile as i32
     } else {
@@ -907,7 +912,7 @@ pub fn get_tile_at(q: i32, r: i32) -> i32 {
 /// the user clicks "Recompute Wave Collapse" to start fresh.
 #[wasm_bindgen]
 pub fn clear_layout() {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     state.clear();
 }
 
@@ -922,7 +927,7 @@ pub fn clear_layout() {
 /// @returns true if constraint was set successfully, false if tile type is invalid
 #[wasm_bindgen]
 pub fn set_pre_constraint(q: i32, r: i32, tile_type: i32) -> bool {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     
     // Convert i32 to TileType
     let tile = match tile_type {
@@ -943,7 +948,7 @@ pub fn set_pre_constraint(q: i32, r: i32, tile_type: i32) -> bool {
 /// completely random layouts again. Useful for resetting after text-guided generation.
 #[wasm_bindgen]
 pub fn clear_pre_constraints() {
-    let mut state = WFC_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&WFC_STATE);
     state.clear_pre_constraints();
 }
 
@@ -957,7 +962,7 @@ pub fn clear_pre_constraints() {
 /// @returns JSON string with tile counts: {"grass":X,"building":Y,"road":Z,"forest":A,"water":B,"total":C}
 #[wasm_bindgen]
 pub fn get_stats() -> String {
-    let state = WFC_STATE.lock().unwrap();
+    let state = lock_or_recover(&WFC_STATE);
     
     let mut grass = 0;
     let mut building = 0;
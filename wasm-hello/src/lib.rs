@@ -1,5 +1,5 @@
 use wasm_bindgen::prelude::*;
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, MutexGuard};
 
 /// Simple state structure for the hello-wasm template
 /// This demonstrates the state management pattern used throughout the project.
@@ -14,8 +14,16 @@ use std::sync::{LazyLock, Mutex};
 struct HelloState {
     /// Counter value that can be incremented
     counter: i32,
+    /// Counter value last passed to `wasm_init`, restored by `reset_counter`
+    initial_counter: i32,
+    /// Smallest value the counter may hold
+    counter_min: i32,
+    /// Largest value the counter may hold
+    counter_max: i32,
     /// Message string that can be set and retrieved
     message: String,
+    /// Ice cream topping string that can be set and retrieved
+    ice_cream_topping: String,
 }
 
 impl HelloState {
@@ -23,7 +31,11 @@ impl HelloState {
     fn new() -> Self {
         HelloState {
             counter: 0,
+            initial_counter: 0,
+            counter_min: i32::MIN,
+            counter_max: i32::MAX,
             message: String::from("Hello from Auburn"),
+            ice_cream_topping: String::from("Sprinkles"),
         }
     }
     
@@ -32,23 +44,69 @@ impl HelloState {
         self.counter
     }
     
-    /// Increment the counter by 1
+    /// Set the counter (clamped into the counter bounds) and remember it as the reset value
+    fn init_counter(&mut self, initial_counter: i32) {
+        self.counter = initial_counter.clamp(self.counter_min, self.counter_max);
+        self.initial_counter = initial_counter;
+    }
+    
+    /// Increment the counter by 1, clamped into the counter bounds
     fn increment_counter(&mut self) {
-        self.counter += 1;
+        self.increment_by(1);
+    }
+    
+    /// Decrement the counter by 1, clamped into the counter bounds
+    fn decrement_counter(&mut self) {
+        self.increment_by(-1);
+    }
+    
+    /// Add a signed amount to the counter, clamped into the counter bounds
+    fn increment_by(&mut self, amount: i32) {
+        self.counter = self.counter
+            .saturating_add(amount)
+            .clamp(self.counter_min, self.counter_max);
+    }
+    
+    /// Set the counter bounds and clamp the current value into them
+    /// Returns false (leaving the bounds unchanged) if min > max
+    fn set_counter_bounds(&mut self, min: i32, max: i32) -> bool {
+        if min > max {
+            return false;
+        }
+        self.counter_min = min;
+        self.counter_max = max;
+        self.counter = self.counter.clamp(min, max);
+        true
+    }
+    
+    /// Get the counter bounds as (min, max)
+    fn get_counter_bounds(&self) -> (i32, i32) {
+        (self.counter_min, self.counter_max)
+    }
+    
+    /// Restore the counter to its initial value, clamped into the current bounds
+    fn reset_counter(&mut self) {
+        self.counter = self.initial_counter.clamp(self.counter_min, self.counter_max);
     }
     
     /// Get the current message
     fn get_message(&self) -> String {
         self.message.clone()
-    fn get_Ice_cream_topping(&self) -> String {
-        self.Ice_cream_topping.clone()
     }
     
     /// Set a new message
     fn set_message(&mut self, message: String) {
         self.message = message;
-    fn set_Ice-cream_topping(&mut self, Ice_cream_topping: String) {
-        self.Ice_crema_topping = Ice_cream_topping;
+    }
+    
+    /// Get the current ice cream topping
+    fn get_ice_cream_topping(&self) -> String {
+        self.ice_cream_topping.clone()
+    }
+    
+    /// Set a new ice cream topping
+    fn set_ice_cream_topping(&mut self, ice_cream_topping: String) {
+        self.ice_cream_topping = ice_cream_topping;
     }
 }
 
@@ -62,6 +120,16 @@ impl HelloState {
 /// getter/setter methods. Then expose them via `#[wasm_bindgen]` functions below.
 static HELLO_STATE: LazyLock<Mutex<HelloState>> = LazyLock::new(|| Mutex::new(HelloState::new()));
 
+/// Lock a state mutex, recovering it if it is poisoned
+/// 
+/// **Learning Point**: If a function panics while holding the lock, the `Mutex` is marked
+/// poisoned and `lock().unwrap()` would panic on every later call, leaving the module
+/// unusable until the page reloads. `into_inner()` hands back the guard anyway; the state
+/// is whatever the panicking call left behind.
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 /// Initialize the WASM module
 /// This is called once when the module is first loaded.
 #[wasm_bindgen(start)]
@@ -75,11 +143,12 @@ pub fn init() {
 /// You can add initialization logic here, such as setting up default values or
 /// preparing resources.
 /// 
-/// @param initial_counter - Optional starting value for the counter (defaults to 0)
+/// @param initial_counter - Optional starting value for the counter (defaults to 0),
+///   clamped into the counter bounds
 #[wasm_bindgen]
 pub fn wasm_init(initial_counter: i32) {
-    let mut state = HELLO_STATE.lock().unwrap();
-    state.counter = initial_counter;
+    let mut state = lock_or_recover(&HELLO_STATE);
+    state.init_counter(initial_counter);
 }
 
 /// Get the current counter value
@@ -91,7 +160,7 @@ pub fn wasm_init(initial_counter: i32) {
 /// @returns The current counter value
 #[wasm_bindgen]
 pub fn get_counter() -> i32 {
-    let state = HELLO_STATE.lock().unwrap();
+    let state = lock_or_recover(&HELLO_STATE);
     state.get_counter()
 }
 
@@ -100,14 +169,70 @@ pub fn get_counter() -> i32 {
 /// **Learning Point**: This demonstrates how to mutate the global state.
 /// We lock the mutex, call a mutable method, and the lock is released automatically.
 /// 
-/// **To extend**: You could add parameters like `increment_by(amount: i32)` to
-/// increment by a specific value instead of always 1.
+/// The result is clamped into the counter bounds (see `set_counter_bounds`) and never overflows.
 #[wasm_bindgen]
 pub fn increment_counter() {
-    let mut state = HELLO_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&HELLO_STATE);
     state.increment_counter();
 }
 
+/// Decrement the counter by 1
+/// 
+/// **Learning Point**: Same pattern as `increment_counter`. The result is clamped into the
+/// counter bounds and never overflows.
+#[wasm_bindgen]
+pub fn decrement_counter() {
+    let mut state = lock_or_recover(&HELLO_STATE);
+    state.decrement_counter();
+}
+
+/// Add a signed amount to the counter
+/// 
+/// **Learning Point**: Plain `+` panics on overflow in debug builds and wraps in release
+/// builds. `saturating_add` instead stops at `i32::MIN`/`i32::MAX`, which is what a UI
+/// counter should do.
+/// 
+/// @param amount - Value to add (negative values subtract)
+#[wasm_bindgen]
+pub fn increment_by(amount: i32) {
+    let mut state = lock_or_recover(&HELLO_STATE);
+    state.increment_by(amount);
+}
+
+/// Restrict the counter to a range, e.g. to match a UI slider
+/// 
+/// **Learning Point**: The bounds live on `HelloState` next to the counter, and every
+/// write to it (increments, `wasm_init`, `reset_counter`) clamps into `[min, max]`. The
+/// defaults are the full `i32` range, so the counter behaves as before until bounds are
+/// set. The current value is clamped immediately when the bounds change.
+/// 
+/// @param min - Smallest allowed counter value
+/// @param max - Largest allowed counter value
+/// @returns true if the bounds were set, false if min > max (bounds unchanged)
+#[wasm_bindgen]
+pub fn set_counter_bounds(min: i32, max: i32) -> bool {
+    let mut state = lock_or_recover(&HELLO_STATE);
+    state.set_counter_bounds(min, max)
+}
+
+/// Get the current counter bounds
+/// 
+/// @returns JSON string: {"min":-2147483648,"max":2147483647}
+#[wasm_bindgen]
+pub fn get_counter_bounds() -> String {
+    let state = lock_or_recover(&HELLO_STATE);
+    let (min, max) = state.get_counter_bounds();
+    format!(r#"{{"min":{},"max":{}}}"#, min, max)
+}
+
+/// Reset the counter to the value last passed to `wasm_init` (0 if it was never called),
+/// clamped into the current counter bounds
+#[wasm_bindgen]
+pub fn reset_counter() {
+    let mut state = lock_or_recover(&HELLO_STATE);
+    state.reset_counter();
+}
+
 /// Get the current message
 /// 
 /// **Learning Point**: Strings in Rust need to be converted to JavaScript strings.
@@ -117,11 +242,8 @@ pub fn increment_counter() {
 /// @returns The current message as a JavaScript string
 #[wasm_bindgen]
 pub fn get_message() -> String {
-    let state = HELLO_STATE.lock().unwrap();
+    let state = lock_or_recover(&HELLO_STATE);
     state.get_message()
- pub fn get_Ice_cream_topping() -> String {
-    let state = HELLO_STATE.lock().unwrap();
-    state.get_Ice_cream_topping()
 }
 
 /// Set a new message
@@ -134,9 +256,64 @@ pub fn get_message() -> String {
 /// @param message - The new message to set
 #[wasm_bindgen]
 pub fn set_message(message: String) {
-    let mut state = HELLO_STATE.lock().unwrap();
+    let mut state = lock_or_recover(&HELLO_STATE);
     state.set_message(message);
-pub fn set_Ice_cream_topping(Ice_cream_topping: String) {
-    let mut state = HELLO_STATE.lock().unwrap();
-    state.set_Ice_cream_topping(Ice_cream_topping);
-}
\ No newline at end of file
+}
+
+/// Get the current ice cream topping
+/// 
+/// **Learning Point**: Rust function names are snake_case, but the TypeScript side
+/// already calls `get_Ice_cream_topping`. `js_name` keeps that exported name while
+/// the Rust code follows Rust naming rules.
+/// 
+/// @returns The current ice cream topping as a JavaScript string
+#[wasm_bindgen(js_name = "get_Ice_cream_topping")]
+pub fn get_ice_cream_topping() -> String {
+    let state = lock_or_recover(&HELLO_STATE);
+    state.get_ice_cream_topping()
+}
+
+/// Set a new ice cream topping
+/// 
+/// **Learning Point**: Same pattern as `set_message`: lock the state, call the
+/// setter method, and let the lock drop when the function returns.
+/// 
+/// @param ice_cream_topping - The new topping to set
+#[wasm_bindgen(js_name = "set_Ice_cream_topping")]
+pub fn set_ice_cream_topping(ice_cream_topping: String) {
+    let mut state = lock_or_recover(&HELLO_STATE);
+    state.set_ice_cream_topping(ice_cream_topping);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn init_and_reset_stay_within_bounds() {
+        let mut state = HelloState::new();
+        assert!(state.set_counter_bounds(0, 10));
+        
+        state.init_counter(25);
+        assert_eq!(state.get_counter(), 10);
+        state.init_counter(-3);
+        assert_eq!(state.get_counter(), 0);
+        
+        state.init_counter(7);
+        state.increment_by(2);
+        assert!(state.set_counter_bounds(0, 5));
+        state.reset_counter();
+        assert_eq!(state.get_counter(), 5);
+        
+        // Widening the bounds again lets reset reach the original value
+        assert!(state.set_counter_bounds(0, 10));
+        state.reset_counter();
+        assert_eq!(state.get_counter(), 7);
+    }
+    
+    #[test]
+    fn ice_cream_topping_round_trips_through_exports() {
+        set_ice_cream_topping("Hot fudge".to_string());
+        assert_eq!(get_ice_cream_topping(), "Hot fudge");
+    }
+}
@@ -196,3 +196,34 @@ pub fn normalize_text(text: &str, language: &str) -> String {
     normalized
 }
 
+
+/// Normalize many strings in one call
+/// Takes a JSON array of strings and returns a JSON array of normalized strings in the same order.
+/// Each string is trimmed, lowercased and has its inner whitespace collapsed to single spaces.
+/// Empty and whitespace-only strings become "".
+/// Returns "[]" if the input is not a JSON array of strings.
+#[wasm_bindgen]
+pub fn normalize_text_batch(texts_json: String) -> String {
+    let texts: Vec<String> = match serde_json::from_str(&texts_json) {
+        Ok(texts) => texts,
+        Err(_) => return String::from("[]"),
+    };
+
+    let normalized: Vec<String> = texts.iter()
+        .map(|text| text.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase())
+        .collect();
+
+    serde_json::to_string(&normalized).unwrap_or_else(|_| String::from("[]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn normalize_text_batch_handles_empty_and_whitespace() {
+        let result = normalize_text_batch(r#"["  Hello   World ", "", "   ", "MIXED\tCase\nText"]"#.to_string());
+        assert_eq!(result, r#"["hello world","","","mixed case text"]"#);
+        assert_eq!(normalize_text_batch("not json".to_string()), "[]");
+    }
+}
@@ -1,7 +1,7 @@
 use wasm_bindgen::prelude::*;
 use image::{io::Reader as ImageReader, ImageFormat, GenericImageView};
 use std::io::Cursor;
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, MutexGuard};
 
 // State management pattern similar to wasm-astar
 // Learned about this pattern from rocket_wasm on github
@@ -38,6 +38,18 @@ impl PreprocessState {
 
 static PREPROCESS_STATE: LazyLock<Mutex<PreprocessState>> = LazyLock::new(|| Mutex::new(PreprocessState::new()));
 
+/// Lock a state mutex, recovering it if a previous call panicked while holding it
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Byte length of a width x height RGBA buffer, or None if it doesn't fit in usize
+/// Widens before multiplying (and checks each step) so large dimensions can't wrap
+/// a u32 product into a small, wrongly-accepted length - usize is 32 bits on wasm32
+fn rgba_byte_len(width: u32, height: u32) -> Option<usize> {
+    (width as usize).checked_mul(height as usize)?.checked_mul(4)
+}
+
 #[wasm_bindgen(start)]
 pub fn init() {
     console_error_panic_hook::set_once();
@@ -170,7 +182,7 @@ pub fn preprocess_image_for_smolvlm_256m(
     
     // Normalize pixel values to [0.0, 1.0] range and convert to Float32Array
     // Output format: [R, G, B, R, G, B, ...] flattened (height * width * 3)
-    let mut normalized_data = Vec::with_capacity((target_width * target_height * 3) as usize);
+    let mut normalized_data = Vec::with_capacity(rgb_img.as_raw().len());
     
     for pixel in rgb_img.pixels() {
         // Normalize each channel: pixel_value / 255.0
@@ -192,7 +204,7 @@ pub fn apply_contrast(
     height: u32,
     contrast: f32,
 ) -> Result<Vec<u8>, JsValue> {
-    if image_data.len() != (width * height * 4) as usize {
+    if rgba_byte_len(width, height) != Some(image_data.len()) {
         return Err(JsValue::from_str("Image data size mismatch"));
     }
     
@@ -234,7 +246,7 @@ pub fn apply_cinematic_filter(
     height: u32,
     intensity: f32,
 ) -> Result<Vec<u8>, JsValue> {
-    if image_data.len() != (width * height * 4) as usize {
+    if rgba_byte_len(width, height) != Some(image_data.len()) {
         return Err(JsValue::from_str("Image data size mismatch"));
     }
     
@@ -301,7 +313,7 @@ pub struct PreprocessStats {
 /// Similar pattern to mouse_move in wasm-astar
 #[wasm_bindgen]
 pub fn set_contrast(contrast: f32) {
-    let state = &mut PREPROCESS_STATE.lock().unwrap();
+    let state = &mut lock_or_recover(&PREPROCESS_STATE);
     state.set_contrast(contrast);
 }
 
@@ -309,21 +321,21 @@ pub fn set_contrast(contrast: f32) {
 /// Similar pattern to mouse_move in wasm-astar
 #[wasm_bindgen]
 pub fn set_cinematic(intensity: f32) {
-    let state = &mut PREPROCESS_STATE.lock().unwrap();
+    let state = &mut lock_or_recover(&PREPROCESS_STATE);
     state.set_cinematic(intensity);
 }
 
 /// Get current contrast value from WASM state
 #[wasm_bindgen]
 pub fn get_contrast() -> f32 {
-    let state = PREPROCESS_STATE.lock().unwrap();
+    let state = lock_or_recover(&PREPROCESS_STATE);
     state.get_contrast()
 }
 
 /// Get current cinematic intensity from WASM state
 #[wasm_bindgen]
 pub fn get_cinematic() -> f32 {
-    let state = PREPROCESS_STATE.lock().unwrap();
+    let state = lock_or_recover(&PREPROCESS_STATE);
     state.get_cinematic()
 }
 
@@ -1,7 +1,8 @@
 use wasm_bindgen::prelude::*;
 use image::{io::Reader as ImageReader, ImageFormat, GenericImageView};
+use std::collections::HashMap;
 use std::io::Cursor;
-use std::sync::{LazyLock, Mutex};
+use std::sync::{LazyLock, Mutex, MutexGuard};
 
 // State management pattern similar to wasm-astar
 // Learned about this pattern from rocket_wasm on github
@@ -38,6 +39,18 @@ impl PreprocessState {
 
 static PREPROCESS_STATE: LazyLock<Mutex<PreprocessState>> = LazyLock::new(|| Mutex::new(PreprocessState::new()));
 
+/// Lock a state mutex, recovering it if a previous call panicked while holding it
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Byte length of a width x height RGBA buffer, or None if it doesn't fit in usize
+/// Widens before multiplying (and checks each step) so large dimensions can't wrap
+/// a u32 product into a small, wrongly-accepted length - usize is 32 bits on wasm32
+fn rgba_byte_len(width: u32, height: u32) -> Option<usize> {
+    (width as usize).checked_mul(height as usize)?.checked_mul(4)
+}
+
 #[wasm_bindgen(start)]
 pub fn init() {
     console_error_panic_hook::set_once();
@@ -170,7 +183,7 @@ pub fn preprocess_image_for_smolvlm(
     
     // Normalize pixel values to [0.0, 1.0] range and convert to Float32Array
     // Output format: [R, G, B, R, G, B, ...] flattened (height * width * 3)
-    let mut normalized_data = Vec::with_capacity((target_width * target_height * 3) as usize);
+    let mut normalized_data = Vec::with_capacity(rgb_img.as_raw().len());
     
     for pixel in rgb_img.pixels() {
         // Normalize each channel: pixel_value / 255.0
@@ -182,6 +195,394 @@ pub fn preprocess_image_for_smolvlm(
     Ok(normalized_data)
 }
 
+/// Preprocess image data by resizing with a blend of the standard and bilinear resizes
+/// Both resizes are computed, then mixed per channel: standard * (1 - w) + bilinear * w
+/// The standard resize is exactly `preprocess_image` (Lanczos3) and the bilinear one is
+/// exactly `preprocess_image_bilinear` on the decoded pixels, so the ends of the knob
+/// reproduce those functions byte for byte
+/// bilinear_weight: 0.0 = pure `preprocess_image` (sharp), 1.0 = pure bilinear (smooth, cheaper)
+/// Values outside [0.0, 1.0] are clamped; NaN is treated as 0.0
+/// Returns preprocessed image data as RGBA bytes
+/// Useful as a single quality/speed knob when benchmarking preprocessing pipelines
+#[wasm_bindgen]
+pub fn preprocess_image_blend(
+    image_data: &[u8],
+    _source_width: u32,
+    _source_height: u32,
+    target_width: u32,
+    target_height: u32,
+    bilinear_weight: f64,
+) -> Result<Vec<u8>, JsValue> {
+    let weight = if bilinear_weight.is_nan() { 0.0 } else { bilinear_weight.clamp(0.0, 1.0) };
+    
+    // Copy the image data into a Vec to ensure proper memory management
+    // This prevents issues with WASM memory deallocation
+    let image_bytes = image_data.to_vec();
+    
+    // Decode image from bytes (supports PNG and JPEG)
+    // Try PNG first, then JPEG
+    let img = ImageReader::with_format(Cursor::new(&image_bytes), ImageFormat::Png)
+        .decode()
+        .or_else(|_| {
+            ImageReader::with_format(Cursor::new(&image_bytes), ImageFormat::Jpeg)
+                .decode()
+        })
+        .map_err(|e| JsValue::from_str(&format!("Failed to decode image: {}", e)))?;
+
+    // Same filter as preprocess_image
+    let standard = img.resize_exact(target_width, target_height, image::imageops::FilterType::Lanczos3).to_rgba8().into_raw();
+    let (source_width, source_height) = img.dimensions();
+    let bilinear = preprocess_image_bilinear(&img.to_rgba8().into_raw(), source_width, source_height, target_width, target_height);
+    
+    // Blend per channel and round back to bytes
+    let blended = standard.iter()
+        .zip(bilinear.iter())
+        .map(|(&s, &b)| (s as f64 * (1.0 - weight) + b as f64 * weight).round() as u8)
+        .collect();
+    
+    Ok(blended)
+}
+
+/// Resize raw RGBA pixels with bilinear interpolation
+/// Unlike the other preprocess_* functions, image_data is raw RGBA (e.g. canvas ImageData),
+/// not an encoded PNG/JPEG, so source_width and source_height are required
+/// Each target pixel center is mapped back into the source image, the four surrounding
+/// source pixels are sampled, and every channel (alpha included) is interpolated
+/// Sample coordinates past the edges are clamped to the border pixels rather than wrapped
+/// Returns resized image data as RGBA bytes, or an empty Vec if image_data is shorter
+/// than source_width * source_height * 4 or any dimension is 0
+#[wasm_bindgen]
+pub fn preprocess_image_bilinear(
+    image_data: &[u8],
+    source_width: u32,
+    source_height: u32,
+    target_width: u32,
+    target_height: u32,
+) -> Vec<u8> {
+    let (src_w, src_h) = (source_width as usize, source_height as usize);
+    let (dst_w, dst_h) = (target_width as usize, target_height as usize);
+    let (src_len, dst_len) = match (rgba_byte_len(source_width, source_height), rgba_byte_len(target_width, target_height)) {
+        (Some(src_len), Some(dst_len)) => (src_len, dst_len),
+        _ => return Vec::new(),
+    };
+    if src_len == 0 || dst_len == 0 || image_data.len() < src_len {
+        return Vec::new();
+    }
+    
+    let scale_x = src_w as f32 / dst_w as f32;
+    let scale_y = src_h as f32 / dst_h as f32;
+    
+    // Map a target coordinate to the two neighboring source coordinates and the blend weight
+    let sample_axis = |dst: usize, scale: f32, src_len: usize| -> (usize, usize, f32) {
+        let src = ((dst as f32 + 0.5) * scale - 0.5).clamp(0.0, (src_len - 1) as f32);
+        let low = src.floor() as usize;
+        let high = (low + 1).min(src_len - 1);
+        (low, high, src - low as f32)
+    };
+    
+    let mut result = Vec::with_capacity(dst_len);
+    for y in 0..dst_h {
+        let (y0, y1, fy) = sample_axis(y, scale_y, src_h);
+        for x in 0..dst_w {
+            let (x0, x1, fx) = sample_axis(x, scale_x, src_w);
+            
+            let top_left = (y0 * src_w + x0) * 4;
+            let top_right = (y0 * src_w + x1) * 4;
+            let bottom_left = (y1 * src_w + x0) * 4;
+            let bottom_right = (y1 * src_w + x1) * 4;
+            
+            for channel in 0..4 {
+                let top = image_data[top_left + channel] as f32 * (1.0 - fx)
+                    + image_data[top_right + channel] as f32 * fx;
+                let bottom = image_data[bottom_left + channel] as f32 * (1.0 - fx)
+                    + image_data[bottom_right + channel] as f32 * fx;
+                let value = top * (1.0 - fy) + bottom * fy;
+                result.push(value.round().clamp(0.0, 255.0) as u8);
+            }
+        }
+    }
+    
+    result
+}
+
+/// Downscale raw RGBA pixels by area averaging (box filter)
+/// The source is split into target_width x target_height boxes (integer boundaries, so
+/// every source pixel lands in exactly one box), and each target pixel is the rounded
+/// per-channel average of its box, alpha included. Fine detail such as a checkerboard
+/// averages out to its mean instead of aliasing the way point sampling does
+/// Downscaling only: upscaling has nothing to average, so use preprocess_image_bilinear
+/// Returns target_width * target_height RGBA bytes, or an empty Vec if image_data is shorter
+/// than source_width * source_height * 4, a target dimension is larger than the source's,
+/// or any dimension is 0
+#[wasm_bindgen]
+pub fn downscale_area(
+    image_data: &[u8],
+    source_width: u32,
+    source_height: u32,
+    target_width: u32,
+    target_height: u32,
+) -> Vec<u8> {
+    let (src_w, src_h) = (source_width as usize, source_height as usize);
+    let (dst_w, dst_h) = (target_width as usize, target_height as usize);
+    let (src_len, dst_len) = match (rgba_byte_len(source_width, source_height), rgba_byte_len(target_width, target_height)) {
+        (Some(src_len), Some(dst_len)) => (src_len, dst_len),
+        _ => return Vec::new(),
+    };
+    if dst_len == 0 || dst_w > src_w || dst_h > src_h || image_data.len() < src_len {
+        return Vec::new();
+    }
+    
+    // Box edges along one axis: target pixel i covers source [i * src / dst, (i + 1) * src / dst)
+    let box_edge = |i: usize, src: usize, dst: usize| -> usize { (i as u64 * src as u64 / dst as u64) as usize };
+    
+    let mut result = Vec::with_capacity(dst_len);
+    for y in 0..dst_h {
+        let (y0, y1) = (box_edge(y, src_h, dst_h), box_edge(y + 1, src_h, dst_h));
+        for x in 0..dst_w {
+            let (x0, x1) = (box_edge(x, src_w, dst_w), box_edge(x + 1, src_w, dst_w));
+            
+            let mut sums = [0u64; 4];
+            for sy in y0..y1 {
+                let row = &image_data[(sy * src_w + x0) * 4..(sy * src_w + x1) * 4];
+                for pixel in row.chunks_exact(4) {
+                    for channel in 0..4 {
+                        sums[channel] += pixel[channel] as u64;
+                    }
+                }
+            }
+            
+            let count = ((x1 - x0) * (y1 - y0)) as u64;
+            for sum in sums {
+                result.push(((sum + count / 2) / count) as u8);
+            }
+        }
+    }
+    
+    result
+}
+
+/// Cut the centered crop_width x crop_height region out of raw RGBA pixels
+/// Crops: keeps the middle of the image at full resolution and discards the borders,
+/// so nothing is distorted but content near the edges is lost
+/// When the leftover margin is odd, the extra pixel is dropped from the right/bottom
+/// Returns the cropped RGBA bytes, or an empty Vec if image_data is shorter than
+/// source_width * source_height * 4, the crop is larger than the source, or any dimension is 0
+#[wasm_bindgen]
+pub fn center_crop(
+    image_data: &[u8],
+    source_width: u32,
+    source_height: u32,
+    crop_width: u32,
+    crop_height: u32,
+) -> Vec<u8> {
+    let (src_w, src_h) = (source_width as usize, source_height as usize);
+    let (crop_w, crop_h) = (crop_width as usize, crop_height as usize);
+    let src_len = match rgba_byte_len(source_width, source_height) {
+        Some(src_len) => src_len,
+        None => return Vec::new(),
+    };
+    if crop_w == 0 || crop_h == 0 || crop_w > src_w || crop_h > src_h || image_data.len() < src_len {
+        return Vec::new();
+    }
+    
+    let left = (src_w - crop_w) / 2;
+    let top = (src_h - crop_h) / 2;
+    
+    let mut result = Vec::with_capacity(crop_w * crop_h * 4);
+    for y in top..top + crop_h {
+        let row_start = (y * src_w + left) * 4;
+        result.extend_from_slice(&image_data[row_start..row_start + crop_w * 4]);
+    }
+    result
+}
+
+/// Fit raw RGBA pixels inside target_width x target_height without distortion
+/// Letterboxes: keeps the whole image, scaled by the largest factor that fits both
+/// dimensions (bilinear, as in preprocess_image_bilinear) and centered, with the
+/// leftover bars filled with fill in every RGB channel and alpha 255
+/// This is the counterpart to center_crop, which fills the target but cuts off content
+/// Returns target_width * target_height RGBA bytes, or an empty Vec if image_data is
+/// shorter than source_width * source_height * 4 or any dimension is 0
+#[wasm_bindgen]
+pub fn letterbox(
+    image_data: &[u8],
+    source_width: u32,
+    source_height: u32,
+    target_width: u32,
+    target_height: u32,
+    fill: u8,
+) -> Vec<u8> {
+    let (src_w, src_h) = (source_width as usize, source_height as usize);
+    let (dst_w, dst_h) = (target_width as usize, target_height as usize);
+    let (src_len, dst_len) = match (rgba_byte_len(source_width, source_height), rgba_byte_len(target_width, target_height)) {
+        (Some(src_len), Some(dst_len)) => (src_len, dst_len),
+        _ => return Vec::new(),
+    };
+    if src_len == 0 || dst_len == 0 || image_data.len() < src_len {
+        return Vec::new();
+    }
+    
+    // Largest scale that fits, keeping at least one pixel in each dimension
+    let scale = (dst_w as f64 / src_w as f64).min(dst_h as f64 / src_h as f64);
+    let fit_w = ((src_w as f64 * scale).round() as usize).clamp(1, dst_w);
+    let fit_h = ((src_h as f64 * scale).round() as usize).clamp(1, dst_h);
+    let fitted = preprocess_image_bilinear(image_data, source_width, source_height, fit_w as u32, fit_h as u32);
+    
+    let mut result = [fill, fill, fill, 255].repeat(dst_len / 4);
+    let left = (dst_w - fit_w) / 2;
+    let top = (dst_h - fit_h) / 2;
+    for (row, fitted_row) in fitted.chunks_exact(fit_w * 4).enumerate() {
+        let row_start = ((top + row) * dst_w + left) * 4;
+        result[row_start..row_start + fit_w * 4].copy_from_slice(fitted_row);
+    }
+    result
+}
+
+/// Normalize raw RGBA pixels into an f32 tensor with per-channel mean/std
+/// Each RGB channel becomes (value / 255 - mean[c]) / std[c]; alpha is dropped
+/// e.g. ImageNet: mean = [0.485, 0.456, 0.406], std = [0.229, 0.224, 0.225]
+/// Output is HWC ordered: [R, G, B, R, G, B, ...] (length = pixel count * 3)
+/// Returns an empty Vec if mean or std does not have exactly 3 values
+#[wasm_bindgen]
+pub fn normalize_image_f32(rgba: &[u8], mean: &[f32], std: &[f32]) -> Vec<f32> {
+    if mean.len() != 3 || std.len() != 3 {
+        return Vec::new();
+    }
+    
+    let mut normalized = Vec::with_capacity(rgba.len() / 4 * 3);
+    for pixel in rgba.chunks_exact(4) {
+        for channel in 0..3 {
+            normalized.push(normalize_channel(pixel[channel], mean[channel], std[channel]));
+        }
+    }
+    normalized
+}
+
+/// Normalize raw RGBA pixels into a channel-first (CHW) f32 tensor
+/// Same normalization as normalize_image_f32, but laid out as in NCHW models:
+/// every red value, then every green value, then every blue value, each plane in pixel order
+/// Returns an empty Vec if mean or std does not have exactly 3 values
+#[wasm_bindgen]
+pub fn normalize_image_f32_chw(rgba: &[u8], mean: &[f32], std: &[f32]) -> Vec<f32> {
+    if mean.len() != 3 || std.len() != 3 {
+        return Vec::new();
+    }
+    
+    let pixel_count = rgba.len() / 4;
+    let mut normalized = Vec::with_capacity(pixel_count * 3);
+    for channel in 0..3 {
+        for pixel in rgba.chunks_exact(4) {
+            normalized.push(normalize_channel(pixel[channel], mean[channel], std[channel]));
+        }
+    }
+    normalized
+}
+
+/// Scale a channel byte to [0.0, 1.0], then subtract the mean and divide by the std
+fn normalize_channel(value: u8, mean: f32, std: f32) -> f32 {
+    (value as f32 / 255.0 - mean) / std
+}
+
+/// Tokenize text with punctuation split into separate tokens, then map tokens to vocab IDs
+/// Every character that is neither alphanumeric nor whitespace becomes its own token,
+/// so "Hello, world!" segments as ["hello", ",", "world", "!"] rather than ["hello,", "world!"]
+/// vocab_json: JSON object mapping token to ID, e.g. {"hello":1,",":2}
+/// unk_id: ID used for tokens missing from the vocab (and for every token if vocab_json is invalid)
+/// Returns token IDs in text order
+#[wasm_bindgen]
+pub fn tokenize_punct(text: &str, vocab_json: &str, unk_id: u32) -> Vec<u32> {
+    let vocab: HashMap<String, u32> = serde_json::from_str(vocab_json).unwrap_or_default();
+    
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    
+    for c in text.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            current.push(c);
+            continue;
+        }
+        
+        // Whitespace and punctuation both end the current word
+        if !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        if !c.is_whitespace() {
+            tokens.push(c.to_string());
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    
+    tokens.iter()
+        .map(|token| vocab.get(token).copied().unwrap_or(unk_id))
+        .collect()
+}
+
+/// Vocabulary-based word tokenizer
+/// Maps each word to a fixed ID from a trained vocab, so the same word always gets the
+/// same ID regardless of where it appears
+/// Lowercases the text and splits on whitespace (use tokenize_punct to split punctuation off)
+#[wasm_bindgen]
+pub struct Tokenizer {
+    vocab: HashMap<String, u32>,
+    unk_id: u32,
+}
+
+#[wasm_bindgen]
+impl Tokenizer {
+    /// Create a tokenizer from a JSON object mapping token to ID, e.g. {"cat":5,"dog":6}
+    /// unk_id starts at 0; change it with set_unk_id
+    /// Returns an error if vocab_json is not a JSON object of non-negative integer IDs
+    #[wasm_bindgen(constructor)]
+    pub fn new(vocab_json: &str) -> Result<Tokenizer, JsValue> {
+        let vocab: HashMap<String, u32> = serde_json::from_str(vocab_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse vocab: {}", e)))?;
+        Ok(Tokenizer { vocab, unk_id: 0 })
+    }
+    
+    /// Set the ID used for words missing from the vocab
+    pub fn set_unk_id(&mut self, unk_id: u32) {
+        self.unk_id = unk_id;
+    }
+    
+    /// Get the ID used for words missing from the vocab
+    pub fn unk_id(&self) -> u32 {
+        self.unk_id
+    }
+    
+    /// Number of entries in the vocab
+    pub fn vocab_size(&self) -> usize {
+        self.vocab.len()
+    }
+    
+    /// Encode text into token IDs in text order
+    /// Words missing from the vocab map to unk_id
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        text.to_lowercase()
+            .split_whitespace()
+            .map(|word| self.vocab.get(word).copied().unwrap_or(self.unk_id))
+            .collect()
+    }
+    
+    /// Encode text into exactly max_len token IDs for batched inference
+    /// Keeps the first max_len tokens and drops the rest, or pads on the right with pad_id
+    pub fn encode_padded(&self, text: &str, max_len: usize, pad_id: u32) -> Vec<u32> {
+        let mut ids = self.encode(text);
+        ids.resize(max_len, pad_id);
+        ids
+    }
+    
+    /// Attention mask matching encode_padded: 1 for each real token, 0 for each pad
+    /// Always max_len long, truncated at the same position as encode_padded
+    pub fn attention_mask(&self, text: &str, max_len: usize) -> Vec<u32> {
+        let real_tokens = self.encode(text).len().min(max_len);
+        let mut mask = vec![1; real_tokens];
+        mask.resize(max_len, 0);
+        mask
+    }
+}
+
 /// Apply contrast enhancement to RGBA image data
 /// contrast: -100.0 to 100.0 (0.0 = no change, positive = increase, negative = decrease)
 /// Returns processed image data as RGBA bytes
@@ -192,7 +593,7 @@ pub fn apply_contrast(
     height: u32,
     contrast: f32,
 ) -> Result<Vec<u8>, JsValue> {
-    if image_data.len() != (width * height * 4) as usize {
+    if rgba_byte_len(width, height) != Some(image_data.len()) {
         return Err(JsValue::from_str("Image data size mismatch"));
     }
     
@@ -234,7 +635,7 @@ pub fn apply_cinematic_filter(
     height: u32,
     intensity: f32,
 ) -> Result<Vec<u8>, JsValue> {
-    if image_data.len() != (width * height * 4) as usize {
+    if rgba_byte_len(width, height) != Some(image_data.len()) {
         return Err(JsValue::from_str("Image data size mismatch"));
     }
     
@@ -277,6 +678,53 @@ pub fn apply_cinematic_filter(
     Ok(result)
 }
 
+/// Convert RGBA image data to a single-channel grayscale buffer
+/// Uses the standard luminance weights: 0.299 * R + 0.587 * G + 0.114 * B, rounded
+/// Alpha is ignored
+/// Returns one byte per pixel (width * height bytes), or an empty Vec if rgba is
+/// shorter than width * height * 4
+#[wasm_bindgen]
+pub fn to_grayscale(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let byte_len = match rgba_byte_len(width, height) {
+        Some(byte_len) if rgba.len() >= byte_len => byte_len,
+        _ => return Vec::new(),
+    };
+    
+    rgba[..byte_len].chunks_exact(4)
+        .map(|pixel| {
+            let luminance = 0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32;
+            luminance.round().clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+/// Pack RGBA bytes into one little-endian u32 per pixel
+/// Byte order matches a Uint32Array view over canvas ImageData on little-endian hosts:
+/// R is the lowest byte, A the highest (0xAABBGGRR)
+/// Returns an error if the byte length is not a multiple of 4
+#[wasm_bindgen]
+pub fn rgba_to_u32(image_data: &[u8]) -> Result<Vec<u32>, JsValue> {
+    if !image_data.len().is_multiple_of(4) {
+        return Err(JsValue::from_str("Image data length must be a multiple of 4"));
+    }
+    
+    Ok(image_data
+        .chunks_exact(4)
+        .map(|pixel| u32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]))
+        .collect())
+}
+
+/// Unpack little-endian u32 pixels back into RGBA bytes
+/// Inverse of rgba_to_u32
+#[wasm_bindgen]
+pub fn u32_to_rgba(pixels: &[u32]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(pixels.len() * 4);
+    for pixel in pixels {
+        result.extend_from_slice(&pixel.to_le_bytes());
+    }
+    result
+}
+
 /// Get preprocessing statistics
 #[wasm_bindgen]
 pub fn get_preprocess_stats(
@@ -301,7 +749,7 @@ pub struct PreprocessStats {
 /// Similar pattern to mouse_move in wasm-astar
 #[wasm_bindgen]
 pub fn set_contrast(contrast: f32) {
-    let state = &mut PREPROCESS_STATE.lock().unwrap();
+    let state = &mut lock_or_recover(&PREPROCESS_STATE);
     state.set_contrast(contrast);
 }
 
@@ -309,21 +757,125 @@ pub fn set_contrast(contrast: f32) {
 /// Similar pattern to mouse_move in wasm-astar
 #[wasm_bindgen]
 pub fn set_cinematic(intensity: f32) {
-    let state = &mut PREPROCESS_STATE.lock().unwrap();
+    let state = &mut lock_or_recover(&PREPROCESS_STATE);
     state.set_cinematic(intensity);
 }
 
 /// Get current contrast value from WASM state
 #[wasm_bindgen]
 pub fn get_contrast() -> f32 {
-    let state = PREPROCESS_STATE.lock().unwrap();
+    let state = lock_or_recover(&PREPROCESS_STATE);
     state.get_contrast()
 }
 
 /// Get current cinematic intensity from WASM state
 #[wasm_bindgen]
 pub fn get_cinematic() -> f32 {
-    let state = PREPROCESS_STATE.lock().unwrap();
+    let state = lock_or_recover(&PREPROCESS_STATE);
     state.get_cinematic()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageOutputFormat, Rgba, RgbaImage};
+
+    // 6x4 PNG with a different color in every pixel
+    fn test_png() -> Vec<u8> {
+        let img = RgbaImage::from_fn(6, 4, |x, y| Rgba([(x * 40) as u8, (y * 60) as u8, ((x + y) * 20) as u8, 255]));
+        let mut bytes = Cursor::new(Vec::new());
+        img.write_to(&mut bytes, ImageOutputFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn blend_weight_zero_matches_preprocess_image() {
+        let png = test_png();
+        let blended = preprocess_image_blend(&png, 6, 4, 3, 2, 0.0).unwrap();
+        assert_eq!(blended, preprocess_image(&png, 6, 4, 3, 2).unwrap());
+    }
+
+    #[test]
+    fn blend_weight_one_matches_bilinear() {
+        let png = test_png();
+        let rgba = image::load_from_memory(&png).unwrap().to_rgba8().into_raw();
+        let blended = preprocess_image_blend(&png, 6, 4, 3, 2, 1.0).unwrap();
+        assert_eq!(blended, preprocess_image_bilinear(&rgba, 6, 4, 3, 2));
+    }
+
+    #[test]
+    fn tokenize_punct_splits_punctuation() {
+        let vocab = r#"{"hello":1,",":2,"world":3,"!":4}"#;
+        assert_eq!(tokenize_punct("Hello, world!", vocab, 0), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn rgba_u32_round_trip_keeps_byte_order() {
+        let rgba = [0x11, 0x22, 0x33, 0x44, 0xAA, 0xBB, 0xCC, 0xDD];
+        let packed = rgba_to_u32(&rgba).unwrap();
+        assert_eq!(packed, vec![0x4433_2211, 0xDDCC_BBAA]);
+        assert_eq!(u32_to_rgba(&packed), rgba);
+        assert_eq!(rgba_to_u32(&u32_to_rgba(&packed)).unwrap(), packed);
+    }
+
+    #[test]
+    fn hwc_and_chw_order_a_2x2_image_differently() {
+        // Channel values encode (pixel, channel) so positions are easy to read off
+        let rgba = [10, 11, 12, 255, 20, 21, 22, 255, 30, 31, 32, 255, 40, 41, 42, 255];
+        let identity = |values: Vec<f32>| -> Vec<u8> { values.iter().map(|v| (v * 255.0).round() as u8).collect() };
+        let (mean, std) = ([0.0; 3], [1.0; 3]);
+
+        let hwc = identity(normalize_image_f32(&rgba, &mean, &std));
+        let chw = identity(normalize_image_f32_chw(&rgba, &mean, &std));
+        assert_eq!(hwc, vec![10, 11, 12, 20, 21, 22, 30, 31, 32, 40, 41, 42]);
+        assert_eq!(chw, vec![10, 20, 30, 40, 11, 21, 31, 41, 12, 22, 32, 42]);
+    }
+
+    #[test]
+    fn encode_padded_truncates_and_pads_with_matching_mask() {
+        let tokenizer = Tokenizer::new(r#"{"the":1,"cat":2,"sat":3,"on":4,"mat":5}"#).unwrap();
+
+        // Longer than max_len: truncated, all real tokens
+        let long = "the cat sat on the mat";
+        assert_eq!(tokenizer.encode_padded(long, 4, 0), vec![1, 2, 3, 4]);
+        assert_eq!(tokenizer.attention_mask(long, 4), vec![1, 1, 1, 1]);
+
+        // Shorter than max_len: padded on the right, pads masked out
+        let short = "the cat";
+        assert_eq!(tokenizer.encode_padded(short, 5, 9), vec![1, 2, 9, 9, 9]);
+        assert_eq!(tokenizer.attention_mask(short, 5), vec![1, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn dimensions_overflowing_u32_are_rejected() {
+        // 65536 * 65536 * 4 wraps to 0 as a u32 product, which used to pass the length check
+        assert_eq!(65536u32.wrapping_mul(65536).wrapping_mul(4), 0);
+        assert_ne!(rgba_byte_len(65536, 65536), Some(0));
+        assert_eq!(rgba_byte_len(u32::MAX, u32::MAX), None);
+
+        assert!(preprocess_image_bilinear(&[], 65536, 65536, 2, 2).is_empty());
+        assert!(preprocess_image_bilinear(&[0; 16], 2, 2, u32::MAX, u32::MAX).is_empty());
+        assert!(center_crop(&[], 65536, 65536, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn checkerboard_downscales_toward_gray() {
+        let size = 16;
+        let checkerboard: Vec<u8> = (0..size * size)
+            .flat_map(|i| {
+                let value = if (i % size + i / size) % 2 == 0 { 255 } else { 0 };
+                [value, value, value, 255]
+            })
+            .collect();
+
+        // Even boxes hold equal black and white: exactly mid-gray
+        let even = downscale_area(&checkerboard, 16, 16, 4, 4);
+        assert_eq!(even.len(), 4 * 4 * 4);
+        assert!(even.chunks_exact(4).all(|pixel| pixel == [128, 128, 128, 255]));
+
+        // Uneven boxes are off by at most one pixel, so still close to gray
+        let uneven = downscale_area(&checkerboard, 16, 16, 5, 5);
+        assert_eq!(uneven.len(), 5 * 5 * 4);
+        assert!(uneven.chunks_exact(4).all(|pixel| pixel[..3].iter().all(|&value| (96..=160).contains(&value))), "{:?}", uneven);
+    }
+}